@@ -1,17 +1,23 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use letterboxed_solver::archive;
 use letterboxed_solver::dictionary::get_dictionary_reader_from_file;
 use letterboxed_solver::dictionary::smart_dict::SmartDictionary;
-use letterboxed_solver::solvers::{a_star, SolverStrategy};
+use letterboxed_solver::solvers::{a_star, registry, SolverStrategy};
 use letterboxed_solver::NYTBoxPuzzle; // Ensure this path is correct
 
 fn get_nyt_example() -> NYTBoxPuzzle {
     // nov_6_2024
-    NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap()
+    archive::load_nyt_archive()
+        .unwrap()
+        .into_iter()
+        .find(|e| e.date == "2024-11-06")
+        .unwrap()
+        .puzzle
 }
 
 /// benchmarks use the big dict
 fn get_smart_dict(puzzle: &NYTBoxPuzzle) -> SmartDictionary {
-    SmartDictionary::new_from_file(&puzzle, get_dictionary_reader_from_file("words_alpha.txt"))
+    SmartDictionary::new_from_file(puzzle, get_dictionary_reader_from_file("words_alpha.txt"))
 }
 
 fn benchmark_a_star(c: &mut Criterion) {
@@ -37,6 +43,20 @@ fn benchmark_a_star_helper(c: &mut Criterion) {
     });
 }
 
+/// every solver in the registry that's quick enough to benchmark, on the same dictionary
+fn benchmark_registry_solvers(c: &mut Criterion) {
+    let puzzle = get_nyt_example();
+    println!("{}", puzzle);
+    let dict = get_smart_dict(&puzzle);
+
+    for name in ["astar", "astar-fast", "iddfs"] {
+        let solver = registry::get_solver(name).unwrap();
+        c.bench_function(&format!("registry: {}", name), |b| {
+            b.iter(|| solver.solve_with_dict(black_box(&puzzle), black_box(&dict)));
+        });
+    }
+}
+
 fn benchmark_pre_dict_smart_dict(c: &mut Criterion) {
     let puzzle = get_nyt_example();
     println!("{}", puzzle);
@@ -50,6 +70,7 @@ criterion_group!(
     benches,
     benchmark_a_star,
     benchmark_pre_dict_smart_dict,
-    benchmark_a_star_helper
+    benchmark_a_star_helper,
+    benchmark_registry_solvers
 );
 criterion_main!(benches);
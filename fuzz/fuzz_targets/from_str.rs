@@ -0,0 +1,27 @@
+//! Parse arbitrary text as a puzzle. It should either work or return an error, never panic.
+//! The first byte is the max number of words, and the rest is the sides.
+#![no_main]
+
+use letterboxed_solver::LBPuzzle;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&max_words, sides)) = data.split_first() else {
+        return;
+    };
+    let Ok(sides) = std::str::from_utf8(sides) else {
+        return;
+    };
+
+    if let Ok(puzzle) = LBPuzzle::<4, 3>::from_str(max_words as usize, sides) {
+        // anything that parses is a real board: 12 distinct letters, which it can print
+        let mut letters: Vec<char> = puzzle.all_letters().chars().collect();
+        letters.sort();
+        letters.dedup();
+        assert_eq!(letters.len(), 12);
+        let _ = puzzle.to_string();
+        let _ = puzzle.validate_solution(&vec![puzzle.all_letters()]);
+    }
+    let _ = LBPuzzle::<3, 2>::from_str(max_words as usize, sides);
+    let _ = LBPuzzle::<1, 1>::from_str(max_words as usize, sides);
+});
@@ -0,0 +1,23 @@
+//! Validate arbitrary words against a fixed puzzle. Every check should agree with the others,
+//! and none of them should panic. The input is the words, one per line.
+#![no_main]
+
+use letterboxed_solver::NYTBoxPuzzle;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+    let words: Vec<String> = text.lines().map(|w| w.to_string()).collect();
+
+    let full = puzzle.validate_solution(&words);
+    let partial = puzzle.validate_partial_solution(&words);
+    let report = puzzle.check_solution(&words, None);
+    if full.is_ok() {
+        assert!(partial.is_ok());
+        assert!(puzzle.validate_coverage(&words));
+    }
+    assert_eq!(report.is_valid(), full.is_ok() && words.len() <= puzzle.max_words());
+});
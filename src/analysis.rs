@@ -0,0 +1,320 @@
+//! Rates how hard a puzzle is, from things we can actually measure about it.
+//!
+//! Every feature is computed against a SmartDictionary, so the rating depends on which dictionary
+//! is used: a puzzle full of obscure words is hard with a common-words dictionary and easy with
+//! a huge one. The features that go into the score are returned alongside it, so callers can see
+//! *why* a puzzle rated the way it did (or come up with their own score).
+//!
+//! Each feature is boiled down to a "hardness" between 0 and 1, and the score is their weighted
+//! average, scaled to 0-100:
+//!
+//! - fewer playable words is harder
+//! - fewer optimal solutions is harder
+//! - rarer words in the friendliest optimal solution is harder
+//! - more letters which only show up in rare words is harder
+//! - fewer ways to continue from a typical word (i.e. a lower branching factor) is harder
+//!
+//! For puzzle authors, count_solutions also checks that a puzzle doesn't have too many solutions.
+
+use crate::dictionary::smart_dict::SmartDictionary;
+use crate::solvers::a_star::{AStarSolver, Objective};
+use crate::solvers::iddfs::IddfsSolver;
+use crate::solvers::SolverStrategy;
+use crate::{LBPuzzle, LBPuzzleSolution};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Words past this line of the source dictionary count as rare.
+/// Our dictionaries are mostly sorted by frequency, so this is "not in the top 5000".
+pub const RARE_RANK: usize = 5000;
+/// Stop counting optimal solutions after this many. Past here, more doesn't make it any easier.
+pub const SOLUTION_CAP: usize = 1000;
+
+/// easy/medium/hard, from the score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// scores below 35 are easy, below 65 medium, the rest hard
+    pub fn from_score(score: f64) -> Self {
+        match score {
+            s if s < 35.0 => Difficulty::Easy,
+            s if s < 65.0 => Difficulty::Medium,
+            _ => Difficulty::Hard,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Medium => write!(f, "medium"),
+            Difficulty::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+/// the measurements a difficulty rating is based on
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DifficultyFeatures {
+    /// words in the dictionary which can be played on the board
+    pub playable_words: usize,
+    /// words per optimal solution, or None if there's no solution
+    pub optimal_words: Option<usize>,
+    /// distinct optimal solutions, up to SOLUTION_CAP
+    pub optimal_solutions: usize,
+    /// the optimal solution with the most common words
+    pub easiest_solution: Option<LBPuzzleSolution>,
+    /// rank (line in the source dictionary) of the rarest word in the easiest solution
+    pub easiest_solution_rank: Option<usize>,
+    /// puzzle letters which only show up in rare words (see RARE_RANK), or in no words at all
+    pub rare_only_letters: Vec<char>,
+    /// the average number of playable words which could follow a playable word
+    pub branching_factor: f64,
+}
+
+/// a puzzle's difficulty, with everything that went into it
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DifficultyRating {
+    /// 0 (trivial) to 100 (unsolvable)
+    pub score: f64,
+    pub difficulty: Difficulty,
+    pub features: DifficultyFeatures,
+}
+
+/// Measure everything that goes into a difficulty rating. See the module docs.
+pub fn difficulty_features<const S: usize, const L: usize>(
+    puzzle: &LBPuzzle<S, L>,
+    dict: &SmartDictionary,
+) -> DifficultyFeatures {
+    let ranks: HashMap<&str, usize> = dict
+        .get_flat_indexed()
+        .iter()
+        .map(|(idx, word)| (word.as_str(), dict.get_rank_by_idx(*idx)))
+        .collect();
+
+    let (optimal_words, optimal_solutions) = IddfsSolver {}
+        .count_optimal_solutions(puzzle, dict, SOLUTION_CAP)
+        .map_or((None, 0), |(words, count)| (Some(words), count));
+    let easiest_solution = AStarSolver::new(1.0)
+        .with_objective(Objective::WordsThenRarity)
+        .solve_with_dict(puzzle, dict);
+    let easiest_solution_rank = easiest_solution
+        .as_ref()
+        .and_then(|words| words.iter().filter_map(|w| ranks.get(w.as_str())).max())
+        .copied();
+
+    let common_letters: BTreeSet<char> = ranks
+        .iter()
+        .filter(|(_, &rank)| rank < RARE_RANK)
+        .flat_map(|(word, _)| word.chars())
+        .collect();
+    let rare_only_letters = puzzle
+        .all_letters()
+        .chars()
+        .filter(|c| !common_letters.contains(c))
+        .collect();
+
+    // every word can be followed by any word starting with its last letter
+    let followers = |word: &str| {
+        word.chars()
+            .last()
+            .and_then(|c| dict.get(c))
+            .map_or(0, |words| words.len())
+    };
+    let branching_factor = match dict.is_empty() {
+        true => 0.0,
+        false => {
+            let total: usize = ranks.keys().map(|w| followers(w)).sum();
+            total as f64 / dict.len() as f64
+        }
+    };
+
+    DifficultyFeatures {
+        playable_words: dict.len(),
+        optimal_words,
+        optimal_solutions,
+        easiest_solution,
+        easiest_solution_rank,
+        rare_only_letters,
+        branching_factor,
+    }
+}
+
+/// Rate a puzzle's difficulty. See the module docs for how the score is put together.
+/// Unsolvable puzzles always score 100.
+pub fn rate_difficulty<const S: usize, const L: usize>(
+    puzzle: &LBPuzzle<S, L>,
+    dict: &SmartDictionary,
+) -> DifficultyRating {
+    let features = difficulty_features(puzzle, dict);
+    let score = match features.optimal_words {
+        None => 100.0,
+        Some(_) => {
+            let hardness = [
+                1.0 - (features.playable_words as f64 / 500.0).min(1.0),
+                // 1 solution is as hard as it gets, SOLUTION_CAP is as easy
+                1.0 - (features.optimal_solutions as f64).ln() / (SOLUTION_CAP as f64).ln(),
+                features
+                    .easiest_solution_rank
+                    .map_or(1.0, |rank| (rank as f64 / (2 * RARE_RANK) as f64).min(1.0)),
+                (features.rare_only_letters.len() as f64 / 3.0).min(1.0),
+                1.0 - (features.branching_factor / 40.0).min(1.0),
+            ];
+            100.0 * hardness.iter().sum::<f64>() / hardness.len() as f64
+        }
+    };
+
+    DifficultyRating {
+        score,
+        difficulty: Difficulty::from_score(score),
+        features,
+    }
+}
+
+/// How many solutions a puzzle has at a given word count, for checking authored puzzles.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolutionCount {
+    /// the word count checked, or None if it was the fewest words possible, and there's no
+    /// solution at all (so there was nothing to check)
+    pub n_words: Option<usize>,
+    /// the solutions found, up to one more than the limit asked for
+    pub solutions: Vec<LBPuzzleSolution>,
+    /// the most solutions the puzzle was allowed to have
+    pub max_solutions: usize,
+}
+
+impl SolutionCount {
+    /// true if there's exactly one solution
+    pub fn is_unique(&self) -> bool {
+        self.solutions.len() == 1
+    }
+
+    /// true if there's at least one solution, but no more than max_solutions
+    pub fn is_within_limit(&self) -> bool {
+        !self.solutions.is_empty() && self.solutions.len() <= self.max_solutions
+    }
+}
+
+/// Check that a puzzle has at least one, but at most "max_solutions" solutions with "n_words"
+/// words (or the fewest words possible, if None). Stops looking as soon as it finds one too many,
+/// so it's cheap even for puzzles with thousands of solutions.
+pub fn count_solutions<const S: usize, const L: usize>(
+    puzzle: &LBPuzzle<S, L>,
+    dict: &SmartDictionary,
+    n_words: Option<usize>,
+    max_solutions: usize,
+) -> SolutionCount {
+    let solver = IddfsSolver {};
+    let n_words = n_words.or_else(|| {
+        solver
+            .count_optimal_solutions(puzzle, dict, 1)
+            .map(|(words, _)| words)
+    });
+    let solutions = match n_words {
+        Some(n) => solver.find_solutions(puzzle, dict, n, max_solutions.saturating_add(1)),
+        None => Vec::new(),
+    };
+    SolutionCount {
+        n_words,
+        solutions,
+        max_solutions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_solutions, rate_difficulty, Difficulty, RARE_RANK};
+    use crate::dictionary::smart_dict::SmartDictionary;
+    use crate::NYTBoxPuzzle;
+
+    #[test]
+    fn test_rate_difficulty() {
+        let puzzle = NYTBoxPuzzle::from_str(3, "erb uln imk jav").unwrap();
+        let words = "bum\nmane\njuvenile\nembark\nmurk\nkale\n";
+        let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+        let rating = rate_difficulty(&puzzle, &dict);
+        let features = &rating.features;
+        assert_eq!(features.playable_words, 6);
+        assert_eq!(features.optimal_words, Some(2));
+        assert_eq!(features.optimal_solutions, 1);
+        assert_eq!(
+            features.easiest_solution,
+            Some(vec!["juvenile".to_string(), "embark".to_string()])
+        );
+        assert_eq!(features.easiest_solution_rank, Some(3));
+        assert!(features.rare_only_letters.is_empty());
+        // everything can be followed by 1 word, except bum (mane or murk)
+        assert_eq!(features.branching_factor, 7.0 / 6.0);
+        assert_eq!(rating.difficulty, Difficulty::Medium);
+
+        // the same words, but pushed way down the dictionary
+        let rare_words = "#\n".repeat(RARE_RANK) + words;
+        let dict = SmartDictionary::new_from_file(&puzzle, rare_words.as_bytes());
+        let rare_rating = rate_difficulty(&puzzle, &dict);
+        assert_eq!(rare_rating.features.rare_only_letters.len(), 12);
+        assert!(rare_rating.score > rating.score);
+        assert_eq!(rare_rating.difficulty, Difficulty::Hard);
+
+        // no solution
+        let dict = SmartDictionary::new_from_file(&puzzle, "bum\nmane\n".as_bytes());
+        let rating = rate_difficulty(&puzzle, &dict);
+        assert_eq!(rating.features.optimal_words, None);
+        assert_eq!(rating.score, 100.0);
+        assert_eq!(rating.difficulty, Difficulty::Hard);
+    }
+
+    #[test]
+    fn test_count_solutions() {
+        let puzzle = NYTBoxPuzzle::from_str(3, "erb uln imk jav").unwrap();
+        let words = "juvenile\nembark\nbum\nmane\nkale\nmurk\n";
+        let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+
+        let count = count_solutions(&puzzle, &dict, None, 1);
+        assert_eq!(count.n_words, Some(2));
+        assert_eq!(
+            count.solutions,
+            [vec!["juvenile".to_string(), "embark".to_string()]]
+        );
+        assert!(count.is_unique());
+        assert!(count.is_within_limit());
+
+        // 3 words: juvenile embark + kale doesn't count, since embark already finishes it
+        let count = count_solutions(&puzzle, &dict, Some(3), 5);
+        assert!(count.solutions.is_empty());
+        assert!(!count.is_within_limit());
+
+        // stops one past the limit
+        let words = "juvenile\nembark\njuvenilembark\nembarkjuvenile\n";
+        let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+        let count = count_solutions(&puzzle, &dict, Some(1), 1);
+        assert_eq!(count.solutions.len(), 2);
+        assert!(!count.is_unique());
+        assert!(!count.is_within_limit());
+        let count = count_solutions(&puzzle, &dict, Some(2), 0);
+        assert_eq!(count.solutions.len(), 1);
+        assert!(!count.is_within_limit());
+        for solution in &count.solutions {
+            assert!(puzzle.validate_solution(solution).is_ok());
+        }
+        // no limit at all
+        let count = count_solutions(&puzzle, &dict, Some(2), usize::MAX);
+        assert_eq!(count.solutions.len(), 2);
+        assert!(count.is_within_limit());
+
+        // no solutions, so no word count either
+        let dict = SmartDictionary::new_from_file(&puzzle, "bum\nmane\n".as_bytes());
+        let count = count_solutions(&puzzle, &dict, None, 1);
+        assert_eq!(count.n_words, None);
+        assert!(count.solutions.is_empty());
+    }
+}
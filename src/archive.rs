@@ -0,0 +1,174 @@
+//! Archives of past puzzles, along with their official answers.
+//!
+//! An archive is a text file with one puzzle per line: its date, its sides, its max number of
+//! words, and then the words of its official answer, if we know it.
+//!
+//! ```text
+//! # comments and blank lines are skipped
+//! 2024-11-06 erb uln imk jav 6 juvenile embark
+//! 2024-12-31 lei xys vuc tok 5
+//! ```
+//!
+//! A history of NYT puzzles is bundled in resources/archives, for tests, benchmarks & analysis to
+//! share. Official answers are checked against their puzzles as they're read, so a typo in an
+//! archive is an error rather than a puzzle nobody can match.
+
+use crate::LBPuzzleError::ArchiveError;
+use crate::{LBPuzzle, LBPuzzleSolution, NYTBoxPuzzle, Result};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// the bundled archive of NYT puzzles, in resources/archives
+pub const NYT_ARCHIVE: &str = "nyt.txt";
+
+/// a single past puzzle
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArchiveEntry<const S: usize, const L: usize> {
+    /// as written in the archive, usually YYYY-MM-DD
+    pub date: String,
+    pub puzzle: LBPuzzle<S, L>,
+    /// the published answer, if we know it
+    pub answer: Option<LBPuzzleSolution>,
+}
+
+/// parse a single line of an archive: "date sides... max_words [answer...]".
+/// returns Ok(None) for blank lines & comments.
+pub fn parse_archive_line<const S: usize, const L: usize>(
+    line_no: usize,
+    line: &str,
+) -> Result<Option<ArchiveEntry<S, L>>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let error = |msg: &str| ArchiveError(format!("line {}: {}", line_no, msg));
+
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.len() < S + 2 {
+        return Err(error(
+            "expected a date, the puzzle's sides, max words, then (optionally) the answer",
+        ));
+    }
+    let max_words = tokens[S + 1]
+        .parse::<usize>()
+        .map_err(|_| error("max words should be a number"))?;
+    let puzzle = LBPuzzle::from_str(max_words, &tokens[1..=S].join(" "))
+        .map_err(|e| error(&e.to_string()))?;
+
+    let answer: LBPuzzleSolution = tokens[S + 2..].iter().map(|w| w.to_lowercase()).collect();
+    let answer = match answer.is_empty() {
+        true => None,
+        false => {
+            let report = puzzle.check_solution(&answer, None);
+            if !report.is_valid() {
+                return Err(error(&format!(
+                    "the answer doesn't solve the puzzle: {}",
+                    report.problems.join(", ")
+                )));
+            }
+            Some(answer)
+        }
+    };
+
+    Ok(Some(ArchiveEntry {
+        date: tokens[0].to_string(),
+        puzzle,
+        answer,
+    }))
+}
+
+/// Go through an archive one puzzle at a time, skipping blank lines & comments.
+pub fn iter_archive<const S: usize, const L: usize>(
+    reader: impl BufRead,
+) -> impl Iterator<Item = Result<ArchiveEntry<S, L>>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| match line {
+            Ok(line) => parse_archive_line(i + 1, &line).transpose(),
+            Err(e) => Some(Err(ArchiveError(format!("line {}: {}", i + 1, e)))),
+        })
+}
+
+/// Read a whole archive, failing on the first bad line.
+pub fn read_archive<const S: usize, const L: usize>(
+    reader: impl BufRead,
+) -> Result<Vec<ArchiveEntry<S, L>>> {
+    iter_archive(reader).collect()
+}
+
+/// Read one of the archives bundled in resources/archives, e.g. NYT_ARCHIVE.
+pub fn load_bundled_archive<const S: usize, const L: usize>(
+    name: &str,
+) -> Result<Vec<ArchiveEntry<S, L>>> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("resources/archives/{}", name));
+    let file = File::open(&path)
+        .map_err(|e| ArchiveError(format!("couldn't open {}: {}", path.display(), e)))?;
+    read_archive(BufReader::new(file))
+}
+
+/// every NYT puzzle we have, oldest first
+pub fn load_nyt_archive() -> Result<Vec<ArchiveEntry<4, 3>>> {
+    load_bundled_archive::<4, 3>(NYT_ARCHIVE)
+}
+
+/// just the puzzle from each entry, for when the dates & answers don't matter
+pub fn nyt_puzzles() -> Result<Vec<NYTBoxPuzzle>> {
+    Ok(load_nyt_archive()?.into_iter().map(|e| e.puzzle).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_nyt_archive, parse_archive_line, read_archive};
+
+    #[test]
+    fn test_parse_archive_line() {
+        let entry = parse_archive_line::<4, 3>(1, "2024-11-06 erb uln imk jav 6 Juvenile embark")
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.date, "2024-11-06");
+        assert_eq!(entry.puzzle.max_words(), 6);
+        assert_eq!(
+            entry.answer,
+            Some(vec!["juvenile".to_string(), "embark".to_string()])
+        );
+
+        let entry = parse_archive_line::<4, 3>(2, "  2024-12-31 lei xys vuc tok 5 ")
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.answer, None);
+
+        assert!(parse_archive_line::<4, 3>(3, "").unwrap().is_none());
+        assert!(
+            parse_archive_line::<4, 3>(4, "# 2024-11-06 erb uln imk jav 6")
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(parse_archive_line::<4, 3>(5, "erb uln imk jav 6").is_err());
+        assert!(parse_archive_line::<4, 3>(6, "2024-11-06 erb uln imk jav six").is_err());
+        // the answer has to actually solve the puzzle
+        assert!(parse_archive_line::<4, 3>(7, "2024-11-06 erb uln imk jav 6 juvenile").is_err());
+        assert!(
+            parse_archive_line::<4, 3>(8, "2024-11-06 erb uln imk jav 1 juvenile embark").is_err()
+        );
+    }
+
+    #[test]
+    fn test_read_archive() {
+        let archive = "# header\n2024-11-06 erb uln imk jav 6 juvenile embark\n\n2024-12-31 lei xys vuc tok 5\n";
+        let entries = read_archive::<4, 3>(archive.as_bytes()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].date, "2024-12-31");
+
+        let error = read_archive::<4, 3>("\n2024-11-06 erb uln imk\n".as_bytes()).unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+
+        // the bundled archive is all good, and in order
+        let entries = load_nyt_archive().unwrap();
+        assert!(!entries.is_empty());
+        assert!(entries.windows(2).all(|e| e[0].date < e[1].date));
+    }
+}
@@ -0,0 +1,209 @@
+//! Solve lots of puzzles in one go, e.g. to regression-test a dictionary against an archive.
+//!
+//! The input has one puzzle per line: its sides, its max number of words, and optionally a date.
+//!
+//! ```text
+//! # comments and blank lines are skipped
+//! erb uln imk jav 6 2024-11-06
+//! lei xys vuc tok 5
+//! ```
+
+use crate::dictionary::smart_dict::SmartDictionary;
+use crate::solvers::SolverStrategy;
+use crate::LBPuzzleError::InputError;
+use crate::{LBPuzzle, LBPuzzleSolution, Result};
+use std::io::BufRead;
+use std::time::{Duration, Instant};
+
+/// a single puzzle from the batch input
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatchEntry<const S: usize, const L: usize> {
+    /// line number in the input, starting from 1
+    pub line: usize,
+    pub puzzle: LBPuzzle<S, L>,
+    pub date: Option<String>,
+}
+
+/// the outcome of one line of the batch input
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BatchResult<const S: usize, const L: usize> {
+    /// the line was a puzzle, and we tried to solve it
+    Attempted {
+        entry: BatchEntry<S, L>,
+        solution: Option<LBPuzzleSolution>,
+        /// time spent filtering the dictionary & solving
+        elapsed: Duration,
+    },
+    /// the line couldn't be parsed
+    Invalid {
+        line: usize,
+        text: String,
+        error: String,
+    },
+}
+
+/// aggregate stats over a whole batch
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatchSummary {
+    pub solved: usize,
+    pub unsolved: usize,
+    pub invalid: usize,
+    /// words across all solutions, for computing the average
+    pub total_words: usize,
+    pub total_time: Duration,
+}
+
+impl BatchSummary {
+    /// the average number of words per solved puzzle, if anything was solved
+    pub fn average_words(&self) -> Option<f64> {
+        if self.solved == 0 {
+            return None;
+        }
+        Some(self.total_words as f64 / self.solved as f64)
+    }
+
+    fn add<const S: usize, const L: usize>(&mut self, result: &BatchResult<S, L>) {
+        match result {
+            BatchResult::Attempted {
+                solution, elapsed, ..
+            } => {
+                match solution {
+                    Some(solution) => {
+                        self.solved += 1;
+                        self.total_words += solution.len();
+                    }
+                    None => self.unsolved += 1,
+                }
+                self.total_time += *elapsed;
+            }
+            BatchResult::Invalid { .. } => self.invalid += 1,
+        }
+    }
+}
+
+/// parse a single line of batch input: "sides... max_words [date]".
+/// returns Ok(None) for blank lines & comments.
+pub fn parse_batch_line<const S: usize, const L: usize>(
+    line_no: usize,
+    line: &str,
+) -> Result<Option<BatchEntry<S, L>>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.len() != S + 1 && tokens.len() != S + 2 {
+        return Err(InputError(
+            "Expected the puzzle's sides, then max words, then (optionally) a date.",
+        ));
+    }
+    let max_words = tokens[S]
+        .parse::<usize>()
+        .map_err(|_| InputError("Max words should be a number."))?;
+    let puzzle = LBPuzzle::from_str(max_words, &tokens[..S].join(" "))?;
+
+    Ok(Some(BatchEntry {
+        line: line_no,
+        puzzle,
+        date: tokens.get(S + 1).map(|d| d.to_string()),
+    }))
+}
+
+/// Solve every puzzle in "reader" using the given raw dictionary (newline-separated words, as in
+/// resources/dictionaries), which is only read from disk once by the caller.
+///
+/// "on_result" is called after each line, so results can be streamed out as they come.
+pub fn solve_batch<const S: usize, const L: usize>(
+    reader: impl BufRead,
+    dictionary_words: &str,
+    solver: &dyn SolverStrategy<S, L>,
+    mut on_result: impl FnMut(&BatchResult<S, L>),
+) -> std::io::Result<BatchSummary> {
+    let mut summary = BatchSummary::default();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let result = match parse_batch_line::<S, L>(i + 1, &line) {
+            Ok(None) => continue,
+            Ok(Some(entry)) => {
+                let start = Instant::now();
+                let dict =
+                    SmartDictionary::new_from_file(&entry.puzzle, dictionary_words.as_bytes());
+                let solution = solver.solve_with_dict(&entry.puzzle, &dict);
+                BatchResult::Attempted {
+                    entry,
+                    solution,
+                    elapsed: start.elapsed(),
+                }
+            }
+            Err(e) => BatchResult::Invalid {
+                line: i + 1,
+                text: line.clone(),
+                error: e.to_string(),
+            },
+        };
+        summary.add(&result);
+        on_result(&result);
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_batch_line, solve_batch, BatchResult};
+    use crate::solvers::iddfs::IddfsSolver;
+
+    #[test]
+    fn test_parse_batch_line() {
+        let entry = parse_batch_line::<4, 3>(1, "erb uln imk jav 6 2024-11-06")
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.puzzle.max_words(), 6);
+        assert_eq!(entry.date.as_deref(), Some("2024-11-06"));
+
+        let entry = parse_batch_line::<4, 3>(2, "  lei xys vuc tok 5 ")
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.date, None);
+
+        assert!(parse_batch_line::<4, 3>(3, "").unwrap().is_none());
+        assert!(parse_batch_line::<4, 3>(4, "# erb uln imk jav 6")
+            .unwrap()
+            .is_none());
+
+        assert!(parse_batch_line::<4, 3>(5, "erb uln imk jav").is_err());
+        assert!(parse_batch_line::<4, 3>(6, "erb uln imk jav six").is_err());
+        assert!(parse_batch_line::<4, 3>(7, "erb uln imk 6").is_err());
+        assert!(parse_batch_line::<4, 3>(8, "erb uln imk jav 6 today extra").is_err());
+    }
+
+    #[test]
+    fn test_solve_batch() {
+        let input = "erb uln imk jav 6 2024-11-06\n\nerb uln imk jav 1\nnonsense\n";
+        let words = "juvenile\nembark\n";
+
+        let mut n_results = 0;
+        let summary = solve_batch(input.as_bytes(), words, &IddfsSolver::<4, 3> {}, |r| {
+            n_results += 1;
+            if let BatchResult::Attempted {
+                solution: Some(solution),
+                ..
+            } = r
+            {
+                assert_eq!(solution, &vec!["juvenile", "embark"]);
+            }
+        })
+        .unwrap();
+
+        assert_eq!(n_results, 3);
+        assert_eq!(summary.solved, 1);
+        assert_eq!(summary.unsolved, 1);
+        assert_eq!(summary.invalid, 1);
+        assert_eq!(summary.average_words(), Some(2.0));
+    }
+}
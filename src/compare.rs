@@ -0,0 +1,136 @@
+//! Compare our solutions against the official answers in an archive, to tune dictionaries.
+//!
+//! For every archived puzzle with a known answer, this checks:
+//! - whether the official answer is reachable, i.e. every one of its words is in our dictionary
+//! - which of its words are missing, if not
+//! - whether our solver's answer is at least as short
+//!
+//! Puzzles without a known answer are skipped.
+
+use crate::archive::ArchiveEntry;
+use crate::dictionary::smart_dict::SmartDictionary;
+use crate::solvers::SolverStrategy;
+use crate::LBPuzzleSolution;
+use std::collections::BTreeSet;
+
+/// how our solver did on one archived puzzle
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnswerComparison {
+    pub date: String,
+    pub official: LBPuzzleSolution,
+    /// our solver's answer, if it found one
+    pub ours: Option<LBPuzzleSolution>,
+    /// words of the official answer which aren't in our dictionary
+    pub missing_words: Vec<String>,
+}
+
+impl AnswerComparison {
+    /// true if we could've found the official answer, i.e. all its words are in our dictionary
+    pub fn official_reachable(&self) -> bool {
+        self.missing_words.is_empty()
+    }
+
+    /// true if our answer has no more words than the official one
+    pub fn as_short(&self) -> bool {
+        self.ours
+            .as_ref()
+            .is_some_and(|ours| ours.len() <= self.official.len())
+    }
+}
+
+/// totals over a whole comparison
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComparisonSummary {
+    pub compared: usize,
+    pub reachable: usize,
+    pub as_short: usize,
+    /// every official word missing from our dictionary, across all puzzles
+    pub missing_words: BTreeSet<String>,
+}
+
+impl ComparisonSummary {
+    fn add(&mut self, comparison: &AnswerComparison) {
+        self.compared += 1;
+        self.reachable += comparison.official_reachable() as usize;
+        self.as_short += comparison.as_short() as usize;
+        self.missing_words
+            .extend(comparison.missing_words.iter().cloned());
+    }
+}
+
+/// Compare against every archived puzzle with a known answer, using the given raw dictionary
+/// (newline-separated words, as in resources/dictionaries).
+///
+/// "on_result" is called after each puzzle, so results can be streamed out as they come.
+pub fn compare_with_answers<const S: usize, const L: usize>(
+    entries: &[ArchiveEntry<S, L>],
+    dictionary_words: &str,
+    solver: &dyn SolverStrategy<S, L>,
+    mut on_result: impl FnMut(&AnswerComparison),
+) -> ComparisonSummary {
+    let mut summary = ComparisonSummary::default();
+
+    for entry in entries {
+        let Some(official) = &entry.answer else {
+            continue;
+        };
+        let dict = SmartDictionary::new_from_file(&entry.puzzle, dictionary_words.as_bytes());
+        let comparison = AnswerComparison {
+            date: entry.date.clone(),
+            official: official.clone(),
+            ours: solver.solve_with_dict(&entry.puzzle, &dict),
+            missing_words: official
+                .iter()
+                .filter(|w| !dict.contains(w))
+                .cloned()
+                .collect(),
+        };
+        summary.add(&comparison);
+        on_result(&comparison);
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compare_with_answers;
+    use crate::archive::read_archive;
+    use crate::solvers::iddfs::IddfsSolver;
+
+    #[test]
+    fn test_compare_with_answers() {
+        let archive = "2024-11-06 erb uln imk jav 6 juvenile embark\n\
+                       2024-11-06 erb uln imk jav 6 murk kanji inviable\n\
+                       2024-12-31 lei xys vuc tok 5\n";
+        let entries = read_archive::<4, 3>(archive.as_bytes()).unwrap();
+        let words = "juvenile\nembark\nmurk\n";
+
+        let mut comparisons = Vec::new();
+        let summary = compare_with_answers(&entries, words, &IddfsSolver {}, |c| {
+            comparisons.push(c.clone())
+        });
+
+        // the puzzle without an answer is skipped
+        assert_eq!(comparisons.len(), 2);
+        assert!(comparisons[0].official_reachable());
+        assert!(comparisons[0].as_short());
+        assert_eq!(
+            comparisons[0].ours,
+            Some(vec!["juvenile".to_string(), "embark".to_string()])
+        );
+        assert_eq!(comparisons[1].missing_words, ["kanji", "inviable"]);
+        assert!(!comparisons[1].official_reachable());
+        assert!(comparisons[1].as_short());
+
+        assert_eq!(summary.compared, 2);
+        assert_eq!(summary.reachable, 1);
+        assert_eq!(summary.as_short, 2);
+        assert_eq!(
+            summary.missing_words.iter().collect::<Vec<_>>(),
+            ["inviable", "kanji"]
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Explains why a puzzle can't be solved with a given dictionary.
+//!
+//! A diagnosis looks for the usual culprits:
+//! - letters no playable word uses at all
+//! - "dead-end" letters, which no playable word starts with. A word ending in one can only ever be
+//!   the last word, so letters which only show up in such words all have to fit in that last word
+//! - how close a solution can get: the most letters any chain of up to max_words words covers,
+//!   with the chain itself as the "nearest miss" (see AStarSolver::solve_best_partial)
+//!
+//! It works on solvable puzzles too, in which case the nearest miss is just a solution.
+
+use crate::dictionary::smart_dict::SmartDictionary;
+use crate::solvers::a_star::AStarSolver;
+use crate::{LBPuzzle, LBPuzzleSolution};
+use std::collections::BTreeSet;
+
+/// everything that stands between a puzzle & a solution
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnosis {
+    /// puzzle letters which no playable word uses
+    pub uncoverable_letters: Vec<char>,
+    /// puzzle letters which no playable word starts with
+    pub dead_end_letters: Vec<char>,
+    /// puzzle letters which are only used by words ending in a dead-end letter, so they can only
+    /// be covered by the last word of a solution
+    pub last_word_only_letters: Vec<char>,
+    /// the chain of words (up to max_words of them) covering the most letters, fewest words first
+    pub nearest_miss: Option<LBPuzzleSolution>,
+    /// puzzle letters the nearest miss doesn't cover
+    pub missing_letters: Vec<char>,
+}
+
+impl Diagnosis {
+    /// true if the nearest miss isn't a miss at all
+    pub fn is_solvable(&self) -> bool {
+        self.nearest_miss.is_some() && self.missing_letters.is_empty()
+    }
+
+    /// human-readable reasons the puzzle can't be solved, empty if it can
+    pub fn reasons(&self) -> Vec<String> {
+        if self.is_solvable() {
+            return Vec::new();
+        }
+        let letters = |letters: &[char]| letters.iter().collect::<String>();
+        let mut reasons = Vec::new();
+        if !self.uncoverable_letters.is_empty() {
+            reasons.push(format!(
+                "No word uses the letters \"{}\".",
+                letters(&self.uncoverable_letters)
+            ));
+        }
+        if self.last_word_only_letters.len() > 1 {
+            reasons.push(format!(
+                "The letters \"{}\" only show up in words ending in \"{}\" (which no word starts \
+                 with), so they all have to fit in the last word.",
+                letters(&self.last_word_only_letters),
+                letters(&self.dead_end_letters)
+            ));
+        }
+        match &self.nearest_miss {
+            Some(words) => reasons.push(format!(
+                "The closest it gets is {:?}, which misses \"{}\".",
+                words,
+                letters(&self.missing_letters)
+            )),
+            None => reasons.push("No words can be played at all.".to_string()),
+        }
+        reasons
+    }
+}
+
+/// Explain what's keeping a puzzle from being solved with this dictionary. See the module docs.
+pub fn diagnose<const S: usize, const L: usize>(
+    puzzle: &LBPuzzle<S, L>,
+    dict: &SmartDictionary,
+) -> Diagnosis {
+    let letters: Vec<char> = puzzle.all_letters().chars().collect();
+    let words = dict.get_flat_indexed();
+
+    let starts: BTreeSet<char> = words.iter().filter_map(|(_, w)| w.chars().next()).collect();
+    let dead_end_letters: Vec<char> = letters
+        .iter()
+        .filter(|c| !starts.contains(c))
+        .copied()
+        .collect();
+    let used_by = |keep: &dyn Fn(&str) -> bool| -> BTreeSet<char> {
+        words
+            .iter()
+            .filter(|(_, w)| keep(w))
+            .flat_map(|(_, w)| w.chars())
+            .collect()
+    };
+    let covered = used_by(&|_| true);
+    let covered_going_on = used_by(&|w| w.chars().last().is_some_and(|c| starts.contains(&c)));
+
+    let nearest_miss = AStarSolver::new(1.0).solve_best_partial(puzzle, dict);
+    let miss_covered: BTreeSet<char> = nearest_miss
+        .iter()
+        .flatten()
+        .flat_map(|w| w.chars())
+        .collect();
+
+    let letters_where =
+        |keep: &dyn Fn(&char) -> bool| letters.iter().filter(|c| keep(c)).copied().collect();
+    Diagnosis {
+        uncoverable_letters: letters_where(&|c| !covered.contains(c)),
+        last_word_only_letters: letters_where(&|c| {
+            covered.contains(c) && !covered_going_on.contains(c)
+        }),
+        dead_end_letters,
+        missing_letters: letters_where(&|c| !miss_covered.contains(c)),
+        nearest_miss,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diagnose;
+    use crate::dictionary::smart_dict::SmartDictionary;
+    use crate::NYTBoxPuzzle;
+
+    #[test]
+    fn test_diagnose() {
+        let puzzle = NYTBoxPuzzle::from_str(3, "erb uln imk jav").unwrap();
+
+        // nothing uses i, and only bum & murk lead anywhere
+        let words = "bum\nmane\nmurk\njuv\nkale\n";
+        let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+        let diagnosis = diagnose(&puzzle, &dict);
+        assert!(!diagnosis.is_solvable());
+        assert_eq!(diagnosis.uncoverable_letters, ['i']);
+        assert!(diagnosis.dead_end_letters.contains(&'v'));
+        assert_eq!(
+            diagnosis.last_word_only_letters,
+            ['e', 'l', 'n', 'j', 'a', 'v']
+        );
+        // bum mane stops at e, so bum murk kale is as close as it gets
+        assert_eq!(
+            diagnosis.nearest_miss,
+            Some(vec![
+                "bum".to_string(),
+                "murk".to_string(),
+                "kale".to_string()
+            ])
+        );
+        assert_eq!(diagnosis.missing_letters, ['n', 'i', 'j', 'v']);
+        assert_eq!(diagnosis.reasons().len(), 3);
+
+        // solvable, so nothing to explain
+        let words = "bum\nmane\njuvenile\nembark\n";
+        let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+        let diagnosis = diagnose(&puzzle, &dict);
+        assert!(diagnosis.is_solvable());
+        assert_eq!(
+            diagnosis.nearest_miss,
+            Some(vec!["juvenile".to_string(), "embark".to_string()])
+        );
+        assert!(diagnosis.reasons().is_empty());
+
+        // too few words allowed: juvenile alone is the closest
+        let puzzle = NYTBoxPuzzle::from_str(1, "erb uln imk jav").unwrap();
+        let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+        let diagnosis = diagnose(&puzzle, &dict);
+        assert_eq!(diagnosis.nearest_miss, Some(vec!["juvenile".to_string()]));
+        assert_eq!(diagnosis.missing_letters, ['r', 'b', 'm', 'k', 'a']);
+
+        // nothing playable at all
+        let dict = SmartDictionary::new_from_file(&puzzle, "".as_bytes());
+        let diagnosis = diagnose(&puzzle, &dict);
+        assert_eq!(diagnosis.nearest_miss, None);
+        assert_eq!(diagnosis.uncoverable_letters.len(), 12);
+        assert_eq!(diagnosis.missing_letters.len(), 12);
+        assert!(!diagnosis.reasons().is_empty());
+    }
+}
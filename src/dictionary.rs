@@ -1,9 +1,13 @@
 use log::debug;
+use std::collections::HashSet;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::Path;
 use trie_rs::{Trie, TrieBuilder};
 
+/// the dictionary used when nobody asks for a specific one
+pub const DEFAULT_DICTIONARY: &str = "google_10000_english.txt";
+
 pub fn get_dictionary_reader_from_file(path: &str) -> BufReader<File> {
     debug!("Loading English dictionary from file...");
     let p = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("resources/dictionaries/{}", path));
@@ -14,7 +18,53 @@ pub fn get_dictionary_reader_from_file(path: &str) -> BufReader<File> {
 }
 
 pub fn get_default_dictionary_reader() -> BufReader<File> {
-    get_dictionary_reader_from_file("google_10000_english.txt")
+    get_dictionary_reader_from_file(DEFAULT_DICTIONARY)
+}
+
+/// like get_dictionary_reader_from_file, but "path" can also be a path to any file on disk,
+/// and a missing file is an error instead of a panic.
+pub fn try_get_dictionary_reader(path: &str) -> io::Result<BufReader<File>> {
+    let p = Path::new(path);
+    let p = if p.is_file() {
+        p.to_path_buf()
+    } else {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("resources/dictionaries/{}", path))
+    };
+    debug!("Loading dictionary from {}...", p.display());
+    Ok(BufReader::new(File::open(p)?))
+}
+
+/// Clean up a raw word list into the format the solvers expect:
+///     - one lowercase word per line
+///     - no comments, blank lines, or words with non-alphabetic characters
+///     - no words shorter than min_length
+///     - no duplicates
+///
+/// The original order is kept, since it's used as a word's rarity.
+/// The writer is flushed before returning, so a failed write is always an error, even if it's
+/// buffered. Returns (# of lines read, # of words written).
+pub fn compile_dictionary(
+    reader: impl BufRead,
+    mut writer: impl Write,
+    min_length: usize,
+) -> io::Result<(usize, usize)> {
+    let mut seen = HashSet::new();
+    let mut n_lines = 0;
+    for line in reader.lines() {
+        n_lines += 1;
+        let word = line?.trim().to_lowercase();
+        if word.starts_with('#')
+            || word.chars().count() < min_length
+            || !word.chars().all(|c| c.is_alphabetic())
+        {
+            continue;
+        }
+        if seen.insert(word.clone()) {
+            writeln!(writer, "{}", word)?;
+        }
+    }
+    writer.flush()?;
+    Ok((n_lines, seen.len()))
 }
 
 pub fn load_trie_dictionary() -> (Trie<u8>, u32) {
@@ -32,7 +82,7 @@ pub fn load_trie_dictionary() -> (Trie<u8>, u32) {
         if word.len() > longest_word {
             longest_word = word.len();
         }
-        words.push(word.to_string());
+        words.push(word);
     }
     debug!(
         "Loaded {} words (longest {}). Building trie...",
@@ -46,7 +96,8 @@ pub fn load_trie_dictionary() -> (Trie<u8>, u32) {
 
 #[cfg(test)]
 mod tests {
-    use super::load_trie_dictionary;
+    use super::{compile_dictionary, load_trie_dictionary};
+    use std::io::{self, BufWriter, Write};
 
     #[test]
     fn test_load_trie_dictionary() {
@@ -55,38 +106,164 @@ mod tests {
 
         assert_eq!(n_words, 5000);
     }
+
+    #[test]
+    fn test_compile_dictionary() {
+        let raw = "# a comment\nHello\nhi\nworld\nhello\ndon't\n\n  Zebra  \n";
+        let mut out = Vec::new();
+        let (n_lines, n_words) = compile_dictionary(raw.as_bytes(), &mut out, 3).unwrap();
+
+        assert_eq!(n_lines, 8);
+        assert_eq!(n_words, 3);
+        assert_eq!(String::from_utf8(out).unwrap(), "hello\nworld\nzebra\n");
+    }
+
+    /// a disk that's always full
+    struct FullDisk;
+    impl Write for FullDisk {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk full"))
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_compile_dictionary_write_error() {
+        // the BufWriter holds onto everything until it's flushed, so the error only shows up then
+        let writer = BufWriter::new(FullDisk);
+        assert!(compile_dictionary("hello\nworld\n".as_bytes(), writer, 3).is_err());
+    }
 }
 
 pub mod smart_dict {
     use crate::LBPuzzle;
     use log::info;
-    use std::collections::{BTreeMap, HashSet};
-    use std::fs::File;
+    use std::collections::{BTreeMap, HashMap, HashSet};
     use std::io::BufRead;
-    use std::io::BufReader;
     use std::rc::Rc;
+    use trie_rs::{Trie, TrieBuilder};
 
     use super::get_default_dictionary_reader;
 
-    pub(crate) struct _Builder(BTreeMap<char, Vec<Rc<String>>>);
+    /// how many playable words contain, start with, and end with a letter
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct LetterStats {
+        pub contains: usize,
+        pub starts: usize,
+        pub ends: usize,
+    }
+
+    /// Everything we learned about the playable words while filtering the source dictionary.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct DictionaryStats {
+        /// lines read from the source dictionary, including comments & junk
+        pub lines_read: usize,
+        /// words which survived filtering (duplicates only count once)
+        pub playable_words: usize,
+        /// number of playable words of each length
+        pub by_length: BTreeMap<usize, usize>,
+        /// per puzzle letter, even the ones no word uses
+        pub letters: BTreeMap<char, LetterStats>,
+        /// side_transitions\[a\]\[b\] = how many times a letter on side a is followed by one on side b,
+        /// across all playable words
+        pub side_transitions: Vec<Vec<usize>>,
+        /// the longest playable word (the first one in the source dictionary, if there's a tie)
+        pub longest_word: Option<String>,
+        /// the playable word which covers the most distinct letters, & how many it covers
+        pub max_coverage: Option<(String, usize)>,
+    }
+
+    impl DictionaryStats {
+        fn new<const S: usize, const L: usize>(puzzle: &LBPuzzle<S, L>) -> Self {
+            Self {
+                letters: puzzle
+                    .all_letters()
+                    .chars()
+                    .map(|c| (c, LetterStats::default()))
+                    .collect(),
+                side_transitions: vec![vec![0; S]; S],
+                ..Default::default()
+            }
+        }
+
+        /// count a playable word. "sides" is the side of each of its letters.
+        fn _add_word(&mut self, word: &str, sides: &[usize]) {
+            let n_letters = word.chars().count();
+            self.playable_words += 1;
+            *self.by_length.entry(n_letters).or_default() += 1;
+
+            let distinct: HashSet<char> = word.chars().collect();
+            for c in &distinct {
+                if let Some(stats) = self.letters.get_mut(c) {
+                    stats.contains += 1;
+                }
+            }
+            if let Some(stats) = word.chars().next().and_then(|c| self.letters.get_mut(&c)) {
+                stats.starts += 1;
+            }
+            if let Some(stats) = word.chars().last().and_then(|c| self.letters.get_mut(&c)) {
+                stats.ends += 1;
+            }
+
+            for pair in sides.windows(2) {
+                self.side_transitions[pair[0]][pair[1]] += 1;
+            }
+            if self
+                .longest_word
+                .as_ref()
+                .is_none_or(|w| w.chars().count() < n_letters)
+            {
+                self.longest_word = Some(word.to_string());
+            }
+            if self
+                .max_coverage
+                .as_ref()
+                .is_none_or(|(_, n)| *n < distinct.len())
+            {
+                self.max_coverage = Some((word.to_string(), distinct.len()));
+            }
+        }
+    }
+
+    /// words grouped by first letter, plus each word's line number in the source dictionary,
+    /// plus stats about the words
+    pub(crate) struct _Builder(
+        BTreeMap<char, Vec<Rc<String>>>,
+        HashMap<Rc<String>, usize>,
+        DictionaryStats,
+    );
 
     impl _Builder {
         /// Sorts all the letters in the dict by length. should be called once after everything's added.
         fn _sort(&mut self) {
             for words in self.0.values_mut() {
-                words.sort_unstable_by(|w1, w2| w2.len().cmp(&w1.len()));
+                words.sort_unstable_by_key(|w| std::cmp::Reverse(w.len()));
             }
         }
 
-        fn _add_word(&mut self, word: String) {
+        /// returns false if the word was already added
+        fn _add_word(&mut self, word: String, rank: usize) -> bool {
             let first_letter = word
                 .chars()
                 .next()
                 .expect("Shouldn't get an empty word here.");
-            self.0
-                .entry(first_letter)
-                .or_insert(Vec::<Rc<String>>::new())
-                .push(Rc::new(word));
+            let word = Rc::new(word);
+            // if a dictionary lists a word twice, only the first one counts
+            if self.1.contains_key(&word) {
+                return false;
+            }
+            self.1.insert(word.clone(), rank);
+            self.0.entry(first_letter).or_default().push(word);
+            true
+        }
+
+        /// line number of a word in the source dictionary
+        pub fn rank(&self, word: &Rc<String>) -> usize {
+            self.1[word]
         }
 
         /// get a flat version of all words in the dictionary, WITH each word given an index
@@ -96,12 +273,16 @@ pub mod smart_dict {
             // TODO change to btree so ordering is semantic and we have indices more naturally
             let noidx = self
                 .0
-                .iter()
-                .flat_map(|(_, words)| words.iter().cloned())
+                .values()
+                .flat_map(|words| words.iter().cloned())
                 .collect::<Vec<Rc<String>>>();
             noidx.iter().cloned().enumerate().collect()
         }
 
+        pub fn stats(&self) -> &DictionaryStats {
+            &self.2
+        }
+
         pub fn take_map(self) -> BTreeMap<char, Vec<Rc<String>>> {
             self.0
         }
@@ -112,7 +293,7 @@ pub mod smart_dict {
         ///     - words are >3 letters
         pub fn new<const S: usize, const L: usize>(
             puzzle: &LBPuzzle<S, L>,
-            dictionary_reader: BufReader<File>,
+            dictionary_reader: impl BufRead,
         ) -> Self {
             // precompute valid word hashes
             let mut side_to_valids: Vec<HashSet<char>> = Vec::new();
@@ -125,17 +306,21 @@ pub mod smart_dict {
                 |idx: i32| side_to_valids.get(idx as usize / L).unwrap_or(&all_valids);
 
             // bookkeeping vars
-            let mut dictionary = Self(BTreeMap::new());
+            let mut dictionary = Self(
+                BTreeMap::new(),
+                HashMap::new(),
+                DictionaryStats::new(puzzle),
+            );
             let mut n_words: u32 = 0;
-
-            let mut n_valid_words: u32 = 0;
             let mut longest_word = 0;
 
             // Iterate over the lines in the file
             'lines: for line in dictionary_reader.lines() {
-                // Add each word to the set (unwrap here for simplicity, but in practice handle errors)
                 n_words += 1;
-                let line = line.unwrap();
+                // skip lines that aren't valid text, rather than giving up on the whole dictionary
+                let Ok(line) = line else {
+                    continue 'lines;
+                };
                 let word = line.trim();
                 if word.len() > longest_word {
                     longest_word = word.len();
@@ -147,10 +332,11 @@ pub mod smart_dict {
                 }
 
                 // evaluate the conditions described above
-                if word.len() < 3 {
+                if word.chars().count() < 3 {
                     continue 'lines;
                 }
                 let mut prev_letter_idx = -1;
+                let mut sides = Vec::with_capacity(word.len());
                 for letter in word.chars() {
                     if !idx_to_valids(prev_letter_idx).contains(&letter) {
                         continue 'lines;
@@ -162,16 +348,19 @@ pub mod smart_dict {
                         .position(|c| c == letter)
                         .expect("letter must exist") as i32;
                     prev_letter_idx = new_idx;
+                    sides.push(new_idx as usize / L);
                 }
                 // if we get here, the word is valid
-                n_valid_words += 1;
-                dictionary._add_word(word.to_string());
+                if dictionary._add_word(word.to_string(), (n_words - 1) as usize) {
+                    dictionary.2._add_word(word, &sides);
+                }
             }
+            dictionary.2.lines_read = n_words as usize;
 
             #[cfg(debug_assertions)]
             info!(
                 "Loaded ({}/{}) words (longest {}). Sorting...",
-                n_valid_words, n_words, longest_word
+                dictionary.2.playable_words, n_words, longest_word
             );
             dictionary._sort();
             #[cfg(debug_assertions)]
@@ -186,6 +375,9 @@ pub mod smart_dict {
     pub struct SmartDictionary {
         _map: BTreeMap<char, Vec<Rc<String>>>,
         _flat: Vec<(usize, Rc<String>)>,
+        // line number of each word in the source dictionary, by stable index
+        _ranks: Vec<usize>,
+        _stats: DictionaryStats,
     }
 
     impl SmartDictionary {
@@ -194,18 +386,31 @@ pub mod smart_dict {
             Self::new_from_file(puzzle, get_default_dictionary_reader())
         }
 
+        /// create the smart dictionary from any source of newline-separated words
+        /// (usually a file from get_dictionary_reader_from_file())
         pub fn new_from_file<const S: usize, const L: usize>(
             puzzle: &LBPuzzle<S, L>,
-            dictionary_reader: BufReader<File>,
+            dictionary_reader: impl BufRead,
         ) -> Self {
             let builder = _Builder::new(puzzle, dictionary_reader);
+            let flat = builder.get_flat_indexed();
 
             Self {
-                _flat: builder.get_flat_indexed(),
+                _ranks: flat.iter().map(|(_, w)| builder.rank(w)).collect(),
+                _stats: builder.stats().clone(),
+                _flat: flat,
                 _map: builder.take_map(),
             }
         }
 
+        /// true if the word is in the dictionary (i.e. it's playable on the puzzle)
+        pub fn contains(&self, word: &str) -> bool {
+            word.chars()
+                .next()
+                .and_then(|c| self.get(c))
+                .is_some_and(|words| words.iter().any(|w| w.as_str() == word))
+        }
+
         /// get all entries under a given letter, or a flattened version with all words.
         pub fn get(&self, c: char) -> Option<&Vec<Rc<String>>> {
             self._map.get(&c)
@@ -250,10 +455,35 @@ pub mod smart_dict {
             Some(self.get_flat_indexed()[idx].1.clone())
         }
 
+        /// get the rank of the word at a given stable index, i.e. its line number in the source
+        /// dictionary. Most of our dictionaries are sorted by frequency, so lower means more common.
+        pub fn get_rank_by_idx(&self, idx: usize) -> usize {
+            self._ranks[idx]
+        }
+
         /// get the total number of words in the dictionary
         pub fn len(&self) -> usize {
             self._flat.len()
         }
+
+        /// build a trie out of all the words in the dictionary, for letter-by-letter lookups
+        pub fn to_trie(&self) -> Trie<u8> {
+            let mut trie = TrieBuilder::<u8>::new();
+            for (_, word) in self.get_flat_indexed() {
+                trie.push(word.as_str());
+            }
+            trie.build()
+        }
+
+        /// stats about the playable words, gathered while filtering the source dictionary
+        pub fn stats(&self) -> &DictionaryStats {
+            &self._stats
+        }
+
+        /// true if no words on the puzzle survived filtering
+        pub fn is_empty(&self) -> bool {
+            self._flat.is_empty()
+        }
     }
 }
 
@@ -271,6 +501,44 @@ mod smart_dict_tests {
         assert!(dict.len() < 370104);
     }
 
+    #[test]
+    fn test_dictionary_stats() {
+        let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+        let words = "# comment\njuvenile\nembark\nbum\nbum\nberry\nmane\nlull\n";
+        let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+        let stats = dict.stats();
+
+        // berry & lull can't be played, and bum only counts once
+        assert_eq!(stats.lines_read, 8);
+        assert_eq!(stats.playable_words, 4);
+        assert_eq!(stats.playable_words, dict.len());
+        assert_eq!(
+            stats.by_length.iter().collect::<Vec<_>>(),
+            [(&3, &1), (&4, &1), (&6, &1), (&8, &1)]
+        );
+
+        assert_eq!(stats.letters.len(), 12);
+        let e = &stats.letters[&'e'];
+        assert_eq!((e.contains, e.starts, e.ends), (3, 1, 2));
+        let m = &stats.letters[&'m'];
+        assert_eq!((m.contains, m.starts, m.ends), (3, 1, 1));
+        assert!(!stats.letters.contains_key(&'y'));
+
+        // every transition is between different sides
+        for (side, transitions) in stats.side_transitions.iter().enumerate() {
+            assert_eq!(transitions[side], 0);
+        }
+        let n_transitions: usize = stats.side_transitions.iter().flatten().sum();
+        assert_eq!(n_transitions, 7 + 5 + 2 + 3);
+        // e->m & r->k in embark, m->b in embark, m->a in mane
+        assert_eq!(stats.side_transitions[0][2], 2);
+        assert_eq!(stats.side_transitions[2][0], 1);
+        assert_eq!(stats.side_transitions[2][3], 1);
+
+        assert_eq!(stats.longest_word.as_deref(), Some("juvenile"));
+        assert_eq!(stats.max_coverage, Some(("juvenile".to_string(), 7)));
+    }
+
     #[test]
     fn test_order_letters() {
         // todo
@@ -0,0 +1,157 @@
+//! The state of a game of Letter Boxed being played by a person, one word at a time.
+//!
+//! Words are checked as they're played, against both the board (per
+//! LBPuzzle::validate_partial_solution) and the dictionary, so a game can never get into a state
+//! that breaks the rules. Bad words are rejected & leave the game as it was.
+
+use crate::dictionary::smart_dict::SmartDictionary;
+use crate::hint::{self, Hint, HintLevel};
+use crate::LBPuzzleError::BadSolutionError;
+use crate::{LBPuzzle, LBPuzzleSolution, Result};
+use std::collections::BTreeSet;
+
+pub struct Game<'a, const S: usize, const L: usize> {
+    puzzle: &'a LBPuzzle<S, L>,
+    dict: &'a SmartDictionary,
+    // the words played so far, in order
+    words: LBPuzzleSolution,
+}
+
+impl<'a, const S: usize, const L: usize> Game<'a, S, L> {
+    pub fn new(puzzle: &'a LBPuzzle<S, L>, dict: &'a SmartDictionary) -> Self {
+        Self {
+            puzzle,
+            dict,
+            words: LBPuzzleSolution::new(),
+        }
+    }
+
+    pub fn puzzle(&self) -> &LBPuzzle<S, L> {
+        self.puzzle
+    }
+
+    /// the words played so far, in order
+    pub fn words(&self) -> &LBPuzzleSolution {
+        &self.words
+    }
+
+    /// Play the next word. If it breaks any rules, it's rejected with an error saying why.
+    pub fn play_word(&mut self, word: &str) -> Result<()> {
+        let word = word.trim().to_lowercase();
+        if self.is_won() {
+            return Err(BadSolutionError(
+                "The puzzle is already solved.".to_string(),
+            ));
+        }
+        if self.words_left() == 0 {
+            return Err(BadSolutionError(format!(
+                "Out of words! (max {})",
+                self.puzzle.max_words()
+            )));
+        }
+
+        let mut words = self.words.clone();
+        words.push(word.clone());
+        self.puzzle.validate_partial_solution(&words)?;
+        // the board's fine with it, but it might not be a real word
+        if !self.dict.contains(&word) {
+            return Err(BadSolutionError(format!(
+                "{} isn't in the dictionary",
+                word
+            )));
+        }
+
+        self.words = words;
+        Ok(())
+    }
+
+    /// take back the last word played, if there is one
+    pub fn undo(&mut self) -> Option<String> {
+        self.words.pop()
+    }
+
+    /// the letter the next word has to start with, if any words have been played
+    pub fn next_letter(&self) -> Option<char> {
+        self.words.last().and_then(|w| w.chars().last())
+    }
+
+    /// every puzzle letter used so far
+    pub fn covered(&self) -> BTreeSet<char> {
+        self.words.iter().flat_map(|w| w.chars()).collect()
+    }
+
+    /// every puzzle letter which still needs using
+    pub fn uncovered(&self) -> BTreeSet<char> {
+        let covered = self.covered();
+        self.puzzle
+            .all_letters()
+            .chars()
+            .filter(|c| !covered.contains(c))
+            .collect()
+    }
+
+    /// how many more words may be played
+    pub fn words_left(&self) -> usize {
+        self.puzzle.max_words().saturating_sub(self.words.len())
+    }
+
+    pub fn is_won(&self) -> bool {
+        !self.words.is_empty() && self.uncovered().is_empty()
+    }
+
+    /// true if we're out of words without having covered everything
+    pub fn is_lost(&self) -> bool {
+        self.words_left() == 0 && !self.is_won()
+    }
+
+    /// A hint for the next word, leading to the shortest finish from here. See hint::next_word_hint.
+    pub fn hint(&self, level: HintLevel) -> Result<Option<Hint>> {
+        hint::next_word_hint(self.puzzle, self.dict, &self.words, level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Game;
+    use crate::dictionary::smart_dict::SmartDictionary;
+    use crate::hint::HintLevel;
+    use crate::NYTBoxPuzzle;
+
+    #[test]
+    fn test_game() {
+        let puzzle = NYTBoxPuzzle::from_str(2, "erb uln imk jav").unwrap();
+        let words = "juvenile\nembark\nbum\nmane\nmurk\n";
+        let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+        let mut game = Game::new(&puzzle, &dict);
+
+        // not a real word, wrong starting letter, and can't be spelled on the board
+        assert!(game.play_word("jive").is_err());
+        assert!(game.play_word("murk").is_ok());
+        assert!(game.play_word("embark").is_err());
+        assert!(game.play_word("kaj").is_err());
+        assert_eq!(game.words(), &vec!["murk"]);
+        assert_eq!(game.next_letter(), Some('k'));
+        assert_eq!(game.words_left(), 1);
+
+        assert_eq!(game.undo(), Some("murk".to_string()));
+        assert_eq!(game.next_letter(), None);
+        assert_eq!(game.uncovered().len(), 12);
+
+        let hint = game.hint(HintLevel::Word).unwrap().unwrap();
+        assert_eq!(hint.word, "juvenile");
+        assert!(game.play_word(" Juvenile ").is_ok());
+        assert!(!game.is_won());
+        assert!(game.play_word("embark").is_ok());
+        assert!(game.is_won());
+        assert!(!game.is_lost());
+        assert!(game.play_word("kaj").is_err());
+
+        // running out of words without covering everything
+        game.undo();
+        game.undo();
+        game.play_word("bum").unwrap();
+        game.play_word("mane").unwrap();
+        assert!(game.is_lost());
+        assert!(game.play_word("embark").is_err());
+    }
+}
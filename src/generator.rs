@@ -0,0 +1,572 @@
+//! Generates new puzzles.
+//!
+//! The recipe is simple: pick S*L distinct letters at random (with some say over which ones),
+//! shuffle them onto the sides, and keep the board only if a solver can actually solve it with the
+//! chosen dictionary. Otherwise, try again with new letters.
+//!
+//! Everything random comes from a seeded PRNG, so the same seed & settings always give the same
+//! puzzle.
+
+use crate::dictionary::smart_dict::SmartDictionary;
+use crate::solvers::a_star::AStarSolver;
+use crate::solvers::iddfs::IddfsSolver;
+use crate::solvers::SolverStrategy;
+use crate::LBPuzzleError::GeneratorError;
+use crate::{LBPuzzle, LBPuzzleSolution, Result};
+use log::info;
+use std::collections::{BTreeSet, HashMap};
+
+const VOWELS: [char; 5] = ['a', 'e', 'i', 'o', 'u'];
+
+/// how often each letter a-z shows up in English text, in hundredths of a percent
+const ENGLISH_FREQUENCIES: [u32; 26] = [
+    817, 149, 278, 425, 1270, 223, 202, 609, 697, 15, 77, 403, 241, 675, 751, 193, 10, 599, 633,
+    906, 276, 98, 236, 15, 197, 7,
+];
+
+/// how likely each letter is to be picked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LetterFrequency {
+    /// every letter is equally likely
+    Uniform,
+    /// letters are picked about as often as they show up in English, so "e" is common & "q" is rare
+    #[default]
+    English,
+}
+
+impl LetterFrequency {
+    fn weight(self, letter: char) -> u32 {
+        match self {
+            LetterFrequency::Uniform => 1,
+            LetterFrequency::English => ENGLISH_FREQUENCIES[(letter as u8 - b'a') as usize],
+        }
+    }
+}
+
+/// a generated puzzle, along with the proof that it's solvable
+#[derive(Debug)]
+pub struct GeneratedPuzzle<const S: usize, const L: usize> {
+    pub puzzle: LBPuzzle<S, L>,
+    /// the solver's solution
+    pub solution: LBPuzzleSolution,
+    /// the number of two-word solutions (as ordered pairs of words)
+    pub two_word_solutions: usize,
+    /// how many boards were tried before this one
+    pub attempts: usize,
+}
+
+/// Makes new puzzles. Configure it with the `with_*` methods, then call `generate`.
+pub struct PuzzleGenerator<'a, const S: usize, const L: usize> {
+    // the raw dictionary, newline-separated (as in resources/dictionaries)
+    dictionary_words: &'a str,
+    seed: u64,
+    max_words: usize,
+    // inclusive range for the number of vowels on the board
+    vowels: (usize, usize),
+    frequency: LetterFrequency,
+    min_two_word_solutions: usize,
+    max_attempts: usize,
+    solver: Box<dyn SolverStrategy<S, L>>,
+}
+
+impl<'a, const S: usize, const L: usize> PuzzleGenerator<'a, S, L> {
+    /// A generator for puzzles using words from "dictionary_words". By default it makes NYT-style
+    /// boards: 5 max words, about a third vowels, English letter frequencies, and verified with
+    /// IddfsSolver.
+    pub fn new(dictionary_words: &'a str) -> Self {
+        let n_letters = S * L;
+        Self {
+            dictionary_words,
+            seed: 0,
+            max_words: 5,
+            vowels: (n_letters / 4, n_letters.div_ceil(3)),
+            frequency: LetterFrequency::default(),
+            min_two_word_solutions: 0,
+            max_attempts: 1000,
+            solver: Box::new(IddfsSolver {}),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_max_words(mut self, max_words: usize) -> Self {
+        self.max_words = max_words;
+        self
+    }
+
+    /// only make boards with between "min" and "max" vowels (inclusive)
+    pub fn with_vowels(mut self, min: usize, max: usize) -> Self {
+        self.vowels = (min, max);
+        self
+    }
+
+    pub fn with_letter_frequency(mut self, frequency: LetterFrequency) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// only keep boards with at least this many two-word solutions
+    pub fn with_min_two_word_solutions(mut self, n: usize) -> Self {
+        self.min_two_word_solutions = n;
+        self
+    }
+
+    /// give up after trying this many boards
+    pub fn with_max_attempts(mut self, n: usize) -> Self {
+        self.max_attempts = n;
+        self
+    }
+
+    /// verify boards with this solver, rather than IddfsSolver
+    pub fn with_solver(mut self, solver: Box<dyn SolverStrategy<S, L>>) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    /// Generate a solvable puzzle, or explain why we couldn't.
+    pub fn generate(&self) -> Result<GeneratedPuzzle<S, L>> {
+        self.check_config()?;
+        let mut rng = _Rng::new(self.seed);
+        // the last board the solver gave up on, to say how close it came
+        let mut last_unsolved = None;
+
+        for attempt in 1..=self.max_attempts {
+            let puzzle = LBPuzzle::new(self.max_words, self.random_sides(&mut rng));
+            let dict = SmartDictionary::new_from_file(&puzzle, self.dictionary_words.as_bytes());
+
+            // counting pairs is cheaper than solving, so do that first if we need to
+            let two_word_solutions = count_two_word_solutions(&puzzle, &dict);
+            if two_word_solutions < self.min_two_word_solutions {
+                continue;
+            }
+            if let Some(solution) = self.solver.solve_with_dict(&puzzle, &dict) {
+                info!("Generated {} after {} attempts", puzzle, attempt);
+                return Ok(GeneratedPuzzle {
+                    puzzle,
+                    solution,
+                    two_word_solutions,
+                    attempts: attempt,
+                });
+            }
+            last_unsolved = Some((puzzle, dict));
+        }
+
+        // best_coverage is a far bigger search than an attempt, so it's only run once, here
+        let closest = match last_unsolved {
+            Some((puzzle, dict)) => format!(
+                " (the last one could cover {} of {} letters)",
+                best_coverage(&puzzle, &dict),
+                S * L
+            ),
+            None => String::new(),
+        };
+        Err(GeneratorError(format!(
+            "no solvable board in {} attempts{}. Try a different seed, or looser settings.",
+            self.max_attempts, closest
+        )))
+    }
+
+    /// Make sure the settings make sense, e.g. that there are enough vowels to go around.
+    /// `generate` does this too, but this is handy for telling bad settings apart from bad luck.
+    pub fn check_config(&self) -> Result<()> {
+        let n_letters = S * L;
+        let (min_vowels, max_vowels) = self.vowels;
+        let n_consonants = 26 - VOWELS.len();
+        if n_letters > 26 {
+            return Err(GeneratorError(format!(
+                "can't fit {} distinct letters on a board",
+                n_letters
+            )));
+        }
+        if min_vowels > max_vowels || min_vowels > VOWELS.len() || min_vowels > n_letters {
+            return Err(GeneratorError(format!(
+                "can't put between {} and {} vowels on a board with {} letters",
+                min_vowels, max_vowels, n_letters
+            )));
+        }
+        if n_letters.saturating_sub(max_vowels.min(VOWELS.len())) > n_consonants {
+            return Err(GeneratorError(format!(
+                "need more than {} vowels to fill a board with {} letters",
+                max_vowels, n_letters
+            )));
+        }
+        if self.max_words == 0 {
+            return Err(GeneratorError("max words must be at least 1".to_string()));
+        }
+        Ok(())
+    }
+
+    /// pick the letters & shuffle them onto the sides
+    fn random_sides(&self, rng: &mut _Rng) -> [[char; L]; S] {
+        let n_letters = S * L;
+        let n_consonants = 26 - VOWELS.len();
+        // the number of vowels, limited so the consonants can fill in the rest
+        let min_vowels = self.vowels.0.max(n_letters.saturating_sub(n_consonants));
+        let max_vowels = self.vowels.1.min(VOWELS.len()).min(n_letters);
+        let n_vowels = min_vowels + rng.below(max_vowels - min_vowels + 1);
+
+        let (vowels, consonants): (Vec<char>, Vec<char>) =
+            ('a'..='z').partition(|c| VOWELS.contains(c));
+        let mut letters = self.pick(rng, vowels, n_vowels);
+        letters.extend(self.pick(rng, consonants, n_letters - n_vowels));
+        rng.shuffle(&mut letters);
+
+        std::array::from_fn(|side| std::array::from_fn(|i| letters[side * L + i]))
+    }
+
+    /// pick "n" distinct letters from "pool", weighted by letter frequency
+    fn pick(&self, rng: &mut _Rng, mut pool: Vec<char>, n: usize) -> Vec<char> {
+        let mut picked = Vec::new();
+        for _ in 0..n {
+            let total: u32 = pool.iter().map(|&c| self.frequency.weight(c)).sum();
+            let mut target = rng.below(total as usize) as u32;
+            let idx = pool
+                .iter()
+                .position(|&c| {
+                    let w = self.frequency.weight(c);
+                    if target < w {
+                        return true;
+                    }
+                    target -= w;
+                    false
+                })
+                .unwrap_or(pool.len() - 1);
+            picked.push(pool.remove(idx));
+        }
+        picked
+    }
+}
+
+/// Construct boards backwards from a known answer, the way NYT does: given seed words (usually
+/// one or two) which chain together & use exactly S*L distinct letters between them, find every
+/// way to split those letters into sides such that the words are still playable.
+///
+/// Arrangements only differ by which letters share a side, so each is returned once, with the
+/// letters in each side sorted & the sides sorted by their first letter.
+pub fn construct_from_words<const S: usize, const L: usize>(
+    seed_words: &[&str],
+    max_words: usize,
+) -> Result<Vec<LBPuzzle<S, L>>> {
+    let words: Vec<String> = seed_words.iter().map(|w| w.trim().to_lowercase()).collect();
+    if words.is_empty() {
+        return Err(GeneratorError("need at least one seed word".to_string()));
+    }
+    if words.len() > max_words {
+        return Err(GeneratorError(format!(
+            "{} seed words won't fit in {} max words",
+            words.len(),
+            max_words
+        )));
+    }
+    for word in &words {
+        if word.chars().count() < 3 {
+            return Err(GeneratorError(format!("{} is <3 letters long", word)));
+        }
+        // the same letter twice in a row is a hop along one side, whatever the board looks like
+        let chars: Vec<char> = word.chars().collect();
+        if chars.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(GeneratorError(format!(
+                "{} has a double letter, so it can't be played on any board",
+                word
+            )));
+        }
+    }
+    for pair in words.windows(2) {
+        if pair[0].chars().last() != pair[1].chars().next() {
+            return Err(GeneratorError(format!(
+                "{} doesn't start with the last letter of {}",
+                pair[1], pair[0]
+            )));
+        }
+    }
+    let mut letters: Vec<char> = words.iter().flat_map(|w| w.chars()).collect();
+    letters.sort();
+    letters.dedup();
+    if letters.len() != S * L {
+        return Err(GeneratorError(format!(
+            "the seed words use {} distinct letters, but the board needs exactly {}",
+            letters.len(),
+            S * L
+        )));
+    }
+
+    // every pair of letters which appear next to each other, and so can't share a side
+    let idx = |c: char| letters.binary_search(&c).unwrap_or_default();
+    let mut neighbors = vec![vec![false; letters.len()]; letters.len()];
+    for word in &words {
+        let chars: Vec<usize> = word.chars().map(idx).collect();
+        for pair in chars.windows(2) {
+            neighbors[pair[0]][pair[1]] = true;
+            neighbors[pair[1]][pair[0]] = true;
+        }
+    }
+
+    let mut arrangements = Vec::new();
+    let mut sides: Vec<Vec<usize>> = Vec::new();
+    _partition::<S, L>(0, &neighbors, &mut sides, &mut |sides| {
+        let sides = std::array::from_fn(|s| std::array::from_fn(|i| letters[sides[s][i]]));
+        arrangements.push(LBPuzzle::new(max_words, sides));
+    });
+    Ok(arrangements)
+}
+
+/// Assign letters "next" and up to sides, calling "found" with every complete assignment.
+/// Letters go in order, & a letter only ever opens the first empty side, so each way of splitting
+/// the letters into sides comes up exactly once.
+fn _partition<const S: usize, const L: usize>(
+    next: usize,
+    neighbors: &[Vec<bool>],
+    sides: &mut Vec<Vec<usize>>,
+    found: &mut impl FnMut(&[Vec<usize>]),
+) {
+    if next == neighbors.len() {
+        found(sides);
+        return;
+    }
+    for s in 0..sides.len() {
+        if sides[s].len() < L && sides[s].iter().all(|&l| !neighbors[l][next]) {
+            sides[s].push(next);
+            _partition::<S, L>(next + 1, neighbors, sides, found);
+            sides[s].pop();
+        }
+    }
+    if sides.len() < S {
+        sides.push(vec![next]);
+        _partition::<S, L>(next + 1, neighbors, sides, found);
+        sides.pop();
+    }
+}
+
+/// How close a puzzle is to solvable: the most letters any chain of up to max_words words covers
+/// (see AStarSolver::solve_best_partial). S*L means it's solvable.
+pub fn best_coverage<const S: usize, const L: usize>(
+    puzzle: &LBPuzzle<S, L>,
+    dict: &SmartDictionary,
+) -> usize {
+    let best = AStarSolver::new(1.0).solve_best_partial(puzzle, dict);
+    let covered: BTreeSet<char> = best.iter().flatten().flat_map(|w| w.chars()).collect();
+    covered.len()
+}
+
+/// Count the two-word solutions to a puzzle, i.e. ordered pairs of dictionary words where the
+/// second starts with the last letter of the first, and together they use every letter.
+/// Pairs where either word uses every letter by itself don't count, since those are really
+/// one-word solutions.
+pub fn count_two_word_solutions<const S: usize, const L: usize>(
+    puzzle: &LBPuzzle<S, L>,
+    dict: &SmartDictionary,
+) -> usize {
+    let letters: Vec<char> = puzzle.all_letters().chars().collect();
+    if letters.len() > u64::BITS as usize || puzzle.max_words() < 2 {
+        return 0;
+    }
+    let full = if letters.len() == 64 {
+        u64::MAX
+    } else {
+        (1u64 << letters.len()) - 1
+    };
+    let mask = |word: &str| {
+        word.chars()
+            .filter_map(|c| letters.iter().position(|&l| l == c))
+            .fold(0u64, |acc, i| acc | (1 << i))
+    };
+
+    // masks of every word, grouped by first letter
+    let mut by_first: HashMap<char, Vec<u64>> = HashMap::new();
+    for (_, word) in dict.get_flat_indexed() {
+        if let Some(first) = word.chars().next() {
+            by_first.entry(first).or_default().push(mask(word));
+        }
+    }
+
+    dict.get_flat_indexed()
+        .iter()
+        .filter_map(|(_, word)| Some((mask(word), by_first.get(&word.chars().last()?)?)))
+        .filter(|(first, _)| *first != full)
+        .map(|(first, seconds)| {
+            seconds
+                .iter()
+                .filter(|&&m| m != full && first | m == full)
+                .count()
+        })
+        .sum()
+}
+
+/// A tiny PRNG (splitmix64), so generated puzzles only depend on the seed, and not on whichever
+/// version of a random number crate we happen to be using.
+struct _Rng(u64);
+
+impl _Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// a number in 0..n. (n must be > 0.) Slightly biased for huge n, which doesn't matter here.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        best_coverage, construct_from_words, count_two_word_solutions, LetterFrequency,
+        PuzzleGenerator, VOWELS,
+    };
+    use crate::dictionary::{self, smart_dict::SmartDictionary};
+    use crate::NYTBoxPuzzle;
+    use std::io::Read;
+
+    fn get_dictionary_words() -> String {
+        let mut words = String::new();
+        dictionary::get_default_dictionary_reader()
+            .read_to_string(&mut words)
+            .unwrap();
+        words
+    }
+
+    #[test]
+    fn test_count_two_word_solutions() {
+        let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+        let words = "juvenile\nembark\nemubark\nbum\nmane\n";
+        let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+        assert_eq!(count_two_word_solutions(&puzzle, &dict), 2);
+
+        // juvenilembark covers everything alone, so following it with kale (or anything) isn't a
+        // two-word solution, and neither is bum followed by mbarkjuvenile
+        let words = "juvenile\nembark\nemubark\nbum\nmane\njuvenilembark\nkale\nmbarkjuvenile\n";
+        let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+        assert_eq!(dict.len(), 8);
+        assert_eq!(count_two_word_solutions(&puzzle, &dict), 2);
+
+        let puzzle = NYTBoxPuzzle::from_str(1, "erb uln imk jav").unwrap();
+        assert_eq!(count_two_word_solutions(&puzzle, &dict), 0);
+    }
+
+    #[test]
+    fn test_best_coverage() {
+        let puzzle = NYTBoxPuzzle::from_str(2, "erb uln imk jav").unwrap();
+        let dict = SmartDictionary::new_from_file(&puzzle, "juvenile\nembark\n".as_bytes());
+        assert_eq!(best_coverage(&puzzle, &dict), 12);
+        // only 1 word allowed, and juvenile covers the most
+        let puzzle = NYTBoxPuzzle::from_str(1, "erb uln imk jav").unwrap();
+        assert_eq!(best_coverage(&puzzle, &dict), 7);
+        let dict = SmartDictionary::new_from_file(&puzzle, "".as_bytes());
+        assert_eq!(best_coverage(&puzzle, &dict), 0);
+    }
+
+    #[test]
+    fn test_generate() {
+        let words = get_dictionary_words();
+        let generator = PuzzleGenerator::<4, 3>::new(&words)
+            .with_seed(42)
+            .with_vowels(3, 4)
+            .with_min_two_word_solutions(1);
+        let generated = generator.generate().unwrap();
+        println!("{:?}", generated);
+
+        let puzzle = &generated.puzzle;
+        assert_eq!(puzzle.max_words(), 5);
+        assert!(puzzle.validate_solution(&generated.solution).is_ok());
+        assert!(generated.solution.len() <= 2);
+        assert!(generated.two_word_solutions >= 1);
+
+        let letters = puzzle.all_letters();
+        let n_vowels = letters.chars().filter(|c| VOWELS.contains(c)).count();
+        assert!((3..=4).contains(&n_vowels));
+        let mut distinct: Vec<char> = letters.chars().collect();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 12);
+
+        // same seed, same puzzle
+        let again = generator.generate().unwrap();
+        assert_eq!(again.puzzle.sides(), puzzle.sides());
+        let other = PuzzleGenerator::<4, 3>::new(&words)
+            .with_seed(43)
+            .with_letter_frequency(LetterFrequency::Uniform)
+            .with_max_words(6)
+            .generate()
+            .unwrap();
+        assert_ne!(other.puzzle.sides(), puzzle.sides());
+    }
+
+    #[test]
+    fn test_generate_bad_config() {
+        let words = "abc\n";
+        assert!(PuzzleGenerator::<9, 3>::new(words).generate().is_err());
+        assert!(PuzzleGenerator::<4, 3>::new(words)
+            .with_vowels(4, 3)
+            .generate()
+            .is_err());
+        assert!(PuzzleGenerator::<4, 3>::new(words)
+            .with_vowels(6, 8)
+            .generate()
+            .is_err());
+        assert!(PuzzleGenerator::<4, 3>::new(words)
+            .with_max_words(0)
+            .generate()
+            .is_err());
+        // valid, but hopeless
+        let err = PuzzleGenerator::<4, 3>::new(words)
+            .with_max_attempts(3)
+            .generate()
+            .unwrap_err();
+        assert!(err.to_string().contains("the last one could cover"));
+    }
+
+    #[test]
+    fn test_construct_from_words() {
+        let seeds = ["juvenile", "embark"];
+        let arrangements = construct_from_words::<4, 3>(&seeds, 6).unwrap();
+        assert!(!arrangements.is_empty());
+        // the actual nov 6, 2024 board is one of them
+        assert!(arrangements.iter().any(|p| p.sides()
+            == [
+                ['a', 'j', 'v'],
+                ['b', 'e', 'r'],
+                ['i', 'k', 'm'],
+                ['l', 'n', 'u']
+            ]));
+        let seeds: Vec<String> = seeds.iter().map(|w| w.to_string()).collect();
+        for puzzle in &arrangements {
+            assert!(puzzle.validate_solution(&seeds).is_ok());
+        }
+        // no duplicates
+        let mut sides: Vec<_> = arrangements.iter().map(|p| p.sides()).collect();
+        sides.sort();
+        sides.dedup();
+        assert_eq!(sides.len(), arrangements.len());
+
+        // a double letter can never be played, wherever it is in the word
+        assert!(construct_from_words::<2, 2>(&["abbcd"], 3).is_err());
+        assert!(construct_from_words::<2, 2>(&["abcdd"], 3).is_err());
+        assert!(construct_from_words::<2, 2>(&["abc", "cdd"], 3).is_err());
+        // a single word works too
+        let arrangements = construct_from_words::<2, 2>(&["abcd"], 3).unwrap();
+        assert_eq!(arrangements.len(), 1);
+        assert_eq!(arrangements[0].sides(), [['a', 'c'], ['b', 'd']]);
+
+        assert!(construct_from_words::<4, 3>(&[], 6).is_err());
+        assert!(construct_from_words::<4, 3>(&["embark", "juvenile"], 6).is_err());
+        assert!(construct_from_words::<4, 3>(&["juvenile"], 6).is_err());
+        assert!(construct_from_words::<4, 3>(&["juvenile", "embark"], 1).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! Hints for a player who's stuck, without giving the whole answer away.
+//!
+//! A hint is about the next word of an optimal solution, picking up from whatever words the player
+//! has entered so far. It starts out vague & can be revealed bit by bit:
+//!
+//! 1. the word's first letter (which is usually already known, unless no words have been entered)
+//! 2. + its length
+//! 3. the whole word
+
+use crate::dictionary::smart_dict::SmartDictionary;
+use crate::solvers::a_star::{AStarSolver, Objective};
+use crate::solvers::SolverStrategy;
+use crate::{LBPuzzle, LBPuzzleSolution, Result};
+use std::fmt;
+
+/// how much of the next word a hint gives away
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum HintLevel {
+    #[default]
+    FirstLetter,
+    Length,
+    Word,
+}
+
+impl HintLevel {
+    /// the level that reveals a little more than this one, if there is one
+    pub fn next(self) -> Option<Self> {
+        match self {
+            HintLevel::FirstLetter => Some(HintLevel::Length),
+            HintLevel::Length => Some(HintLevel::Word),
+            HintLevel::Word => None,
+        }
+    }
+}
+
+/// a suggested next word, only part of which may be revealed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub level: HintLevel,
+    /// the full word. it's up to the caller not to peek!
+    pub word: String,
+    /// the number of words needed to finish the puzzle, including this one
+    pub words_to_finish: usize,
+}
+
+impl Hint {
+    pub fn first_letter(&self) -> char {
+        self.word.chars().next().unwrap_or_default()
+    }
+
+    /// reveal a little more of the word. returns false if it was already fully revealed.
+    pub fn reveal_more(&mut self) -> bool {
+        match self.level.next() {
+            Some(level) => {
+                self.level = level;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for Hint {
+    /// only shows as much as the hint's level allows
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.level {
+            HintLevel::FirstLetter => {
+                write!(f, "The next word starts with \"{}\"", self.first_letter())
+            }
+            HintLevel::Length => write!(
+                f,
+                "The next word starts with \"{}\" and has {} letters",
+                self.first_letter(),
+                self.word.chars().count()
+            ),
+            HintLevel::Word => write!(f, "The next word is \"{}\"", self.word),
+        }
+    }
+}
+
+/// Suggest the next word after "entered", such that the puzzle can still be finished in as few
+/// words as possible. Ties go to more common words, since those make for friendlier hints.
+///
+/// Returns an error if "entered" breaks the rules, and Ok(None) if there's nothing to suggest:
+/// either the puzzle is already solved, or it can't be finished from here.
+pub fn next_word_hint<const S: usize, const L: usize>(
+    puzzle: &LBPuzzle<S, L>,
+    dict: &SmartDictionary,
+    entered: &LBPuzzleSolution,
+    level: HintLevel,
+) -> Result<Option<Hint>> {
+    puzzle.validate_partial_solution(entered)?;
+
+    let solver = AStarSolver::new(1.0).with_objective(Objective::WordsThenRarity);
+    let rest = solver.solve_remainder(puzzle, dict, entered);
+    Ok(rest.and_then(|rest| {
+        let word = rest.first()?.clone();
+        Some(Hint {
+            level,
+            word,
+            words_to_finish: rest.len(),
+        })
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_word_hint, HintLevel};
+    use crate::dictionary::smart_dict::SmartDictionary;
+    use crate::NYTBoxPuzzle;
+
+    #[test]
+    fn test_next_word_hint() {
+        let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+        let words = "juvenile\nembark\nbum\nmane\n";
+        let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+
+        let mut hint = next_word_hint(&puzzle, &dict, &vec![], HintLevel::FirstLetter)
+            .unwrap()
+            .unwrap();
+        assert_eq!(hint.to_string(), "The next word starts with \"j\"");
+        assert_eq!(hint.words_to_finish, 2);
+        assert!(hint.reveal_more());
+        assert_eq!(
+            hint.to_string(),
+            "The next word starts with \"j\" and has 8 letters"
+        );
+        assert!(hint.reveal_more());
+        assert_eq!(hint.to_string(), "The next word is \"juvenile\"");
+        assert!(!hint.reveal_more());
+
+        // entered words don't need to be in the dictionary
+        let entered = vec!["jive".to_string()];
+        assert!(next_word_hint(&puzzle, &dict, &entered, HintLevel::Word)
+            .unwrap()
+            .is_none());
+        let entered = vec!["juvenile".to_string()];
+        let hint = next_word_hint(&puzzle, &dict, &entered, HintLevel::Word)
+            .unwrap()
+            .unwrap();
+        assert_eq!(hint.word, "embark");
+        assert_eq!(hint.words_to_finish, 1);
+
+        // nothing left to hint at
+        let entered = vec!["juvenile".to_string(), "embark".to_string()];
+        assert!(next_word_hint(&puzzle, &dict, &entered, HintLevel::Word)
+            .unwrap()
+            .is_none());
+
+        // breaking the rules is an error
+        let entered = vec!["jam".to_string()];
+        assert!(next_word_hint(&puzzle, &dict, &entered, HintLevel::Word).is_err());
+    }
+}
@@ -1,9 +1,23 @@
+use crate::dictionary::smart_dict::SmartDictionary;
 use crate::LBPuzzleError::BadSolutionError;
+use log::debug;
 use std::collections::HashSet;
 use std::fmt;
 
+pub mod analysis;
+pub mod archive;
+pub mod batch;
+pub mod compare;
+pub mod diagnosis;
 pub mod dictionary;
+pub mod game;
+pub mod generator;
+pub mod hint;
+pub mod render;
+#[cfg(feature = "serde")]
+mod serialize;
 pub mod solvers;
+pub mod svg;
 
 /// Top-level representation of a puzzle definition.
 /// Does not contain the answer to the puzzle--merely its definition.
@@ -22,11 +36,44 @@ pub struct LBPuzzle<const NSIDES: usize, const NLETTERS: usize> {
 pub enum LBPuzzleError<'a> {
     InputError(&'a str),
     BadSolutionError(String),
+    // bad solver name or options, see solvers::registry
+    SolverConfigError(String),
+    // bad generator settings, or no luck generating, see generator
+    GeneratorError(String),
+    // a bad line in a puzzle archive, see archive
+    ArchiveError(String),
+}
+
+impl fmt::Display for LBPuzzleError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LBPuzzleError::InputError(msg) => write!(f, "Bad input: {}", msg),
+            BadSolutionError(msg) => write!(f, "Bad solution: {}", msg),
+            LBPuzzleError::SolverConfigError(msg) => write!(f, "Bad solver config: {}", msg),
+            LBPuzzleError::GeneratorError(msg) => write!(f, "Couldn't generate puzzle: {}", msg),
+            LBPuzzleError::ArchiveError(msg) => write!(f, "Bad archive: {}", msg),
+        }
+    }
 }
 
 /// just a list of the words used to solve, in order
 type LBPuzzleSolution = Vec<String>;
 
+/// everything wrong with a solution, per LBPuzzle::check_solution
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolutionReport {
+    pub words: LBPuzzleSolution,
+    /// human-readable, empty if the solution's valid
+    pub problems: Vec<String>,
+}
+
+impl SolutionReport {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
 type Result<T> = std::result::Result<T, LBPuzzleError<'static>>;
 
 /// The standard Letter Boxed puzzle from NYT.
@@ -52,22 +99,39 @@ impl<const S: usize, const L: usize> LBPuzzle<S, L> {
 
     /// constructs the LBPuzzle from a space-separated string
     pub fn from_str(max_words: usize, sides_str: &str) -> Result<Self> {
-        let mut sides = [[' '; L]; S];
+        let sides: Vec<&str> = sides_str.split_whitespace().collect();
+        Self::from_sides(max_words, &sides)
+    }
 
-        let sides_str = sides_str.to_lowercase();
-        let _s_vec: Vec<&str> = sides_str.split_whitespace().collect();
+    /// constructs the LBPuzzle from its sides (one string each), checking it's a real board:
+    /// S sides of L letters, no letter on the board twice, and room for at least one word.
+    /// Letters are lowercased. Everything that reads a puzzle in (from_str, serde) goes through this.
+    pub fn from_sides<T: AsRef<str>>(max_words: usize, sides_strs: &[T]) -> Result<Self> {
         // sanity check the number of sides
-        if _s_vec.len() != S {
+        if sides_strs.len() != S {
             return Err(LBPuzzleError::InputError("Wrong number of sides."));
         }
 
         // loop through and create the char array
-        for (i, _l_vec) in _s_vec.iter().enumerate() {
-            sides[i] = match _l_vec.chars().collect::<Vec<char>>().try_into() {
+        let mut sides = [[' '; L]; S];
+        for (side, letters) in sides.iter_mut().zip(sides_strs) {
+            let letters: Vec<char> = letters.as_ref().to_lowercase().chars().collect();
+            if letters.iter().any(|c| c.is_whitespace()) {
+                return Err(LBPuzzleError::InputError("Sides can't contain whitespace."));
+            }
+            *side = match letters.try_into() {
                 Ok(s) => s,
                 Err(_) => return Err(LBPuzzleError::InputError("wrong letters")),
             }
         }
+        // a letter on the board twice would make the sides ambiguous
+        let mut seen = HashSet::new();
+        if !sides.iter().flatten().all(|c| seen.insert(*c)) {
+            return Err(LBPuzzleError::InputError("Letters can't repeat."));
+        }
+        if max_words == 0 {
+            return Err(LBPuzzleError::InputError("Max words must be at least 1."));
+        }
         let puzzle = LBPuzzle::new(max_words, sides);
         Ok(puzzle)
     }
@@ -140,28 +204,84 @@ impl<const S: usize, const L: usize> LBPuzzle<S, L> {
 
     /// See if we can solve the puzzle given a solution
     pub fn validate_solution(&self, solution: &LBPuzzleSolution) -> Result<()> {
+        let (flat_solution, visited_letters) = self.trace_solution(solution)?;
+
+        // make sure we visited all the letters
+        for side in visited_letters {
+            if !side.iter().all(|&x| x) {
+                return Err(BadSolutionError("Not all letters were used.".to_string()));
+            }
+        }
+
+        debug!("Validated: {} ✅", flat_solution);
+        Ok(())
+    }
+
+    /// Like validate_solution, but collects every problem instead of stopping at the first one:
+    /// the rules of the board, the max number of words, and (given a dictionary) whether every
+    /// word is real.
+    pub fn check_solution(
+        &self,
+        solution: &LBPuzzleSolution,
+        dict: Option<&SmartDictionary>,
+    ) -> SolutionReport {
+        let mut problems = Vec::new();
+        if let Err(e) = self.validate_solution(solution) {
+            problems.push(e.to_string());
+        }
+        if solution.len() > self.max_words {
+            problems.push(format!(
+                "Too many words ({} > {})",
+                solution.len(),
+                self.max_words
+            ));
+        }
+        if let Some(dict) = dict {
+            for word in solution {
+                if !dict.contains(word) {
+                    problems.push(format!("\"{}\" isn't in the dictionary", word));
+                }
+            }
+        }
+        SolutionReport {
+            words: solution.clone(),
+            problems,
+        }
+    }
+
+    /// See if a solution that's still in progress could be the start of a real one, i.e. it
+    /// follows all the rules, but doesn't necessarily touch every letter yet.
+    /// An empty solution is trivially fine.
+    pub fn validate_partial_solution(&self, solution: &LBPuzzleSolution) -> Result<()> {
+        self.trace_solution(solution)?;
+        Ok(())
+    }
+
+    /// Checks everything about a solution except coverage: word lengths, chaining, and whether we
+    /// can travel around the board with its letters.
+    /// Returns the merged sequence of letters, and which puzzle letters it visited.
+    fn trace_solution(&self, solution: &LBPuzzleSolution) -> Result<(String, [[bool; L]; S])> {
         // for NYT, all words must be 3 letters or more, so check that
         for word in solution {
-            if word.len() < 3 {
+            if word.chars().count() < 3 {
                 return Err(BadSolutionError(format!("{} is <3 letters long", word)));
             }
         }
         // merge the words into a simple sequence of letters & check that the start & end chars match
-        let mut flat_solution = solution.get(0).unwrap().clone();
-        for word in &solution[1..] {
+        let mut flat_solution = solution.first().cloned().unwrap_or_default();
+        for word in solution.iter().skip(1) {
             if word.chars().next() != flat_solution.chars().last() {
                 return Err(BadSolutionError(
                     "Start & end letters don't match".to_string(),
                 ));
             }
-            flat_solution.push_str(&word[1..]);
+            flat_solution.extend(word.chars().skip(1));
         }
 
         // validate that we can travel around the board with these letters,
-        // AND that we touch all of them when we do.
+        // and keep track of which ones we touch when we do.
         let mut visited_letters = [[false; L]; S];
 
-        print!("Validated: ");
         let mut prev_side = -1;
         'letters: for letter in flat_solution.chars() {
             'sides: for (i, side) in self.sides().iter().enumerate() {
@@ -170,7 +290,6 @@ impl<const S: usize, const L: usize> LBPuzzle<S, L> {
                 }
                 let idx = side.iter().position(|_l| letter.eq(_l));
                 if let Some(idx) = idx {
-                    print!("{}", letter);
                     prev_side = i as i32;
                     visited_letters[i][idx] = true;
                     continue 'letters;
@@ -182,15 +301,7 @@ impl<const S: usize, const L: usize> LBPuzzle<S, L> {
             )));
         }
 
-        // make sure we visited all the letters
-        for side in visited_letters {
-            if !side.iter().all(|&x| x) {
-                return Err(BadSolutionError("Not all letters were used.".to_string()));
-            }
-        }
-
-        println!("✅");
-        Ok(())
+        Ok((flat_solution, visited_letters))
     }
 }
 
@@ -230,6 +341,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_validate_partial_solution() {
+        let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+        let valids = [
+            vec![],
+            vec!["juvenile".to_string()],
+            vec!["murk".to_string(), "kanji".to_string()],
+        ];
+        let invalids = [
+            vec!["poop".to_string()],
+            vec!["ju".to_string()],
+            vec!["murk".to_string(), "juvenile".to_string()],
+        ];
+
+        for example in valids {
+            assert!(puzzle.validate_partial_solution(&example).is_ok());
+        }
+        for example in invalids {
+            assert!(puzzle.validate_partial_solution(&example).is_err());
+        }
+        // partial solutions aren't full ones
+        assert!(puzzle.validate_solution(&vec![]).is_err());
+    }
+
     #[test]
     fn test_from_str() {
         let sides_a = [
@@ -246,11 +381,16 @@ mod tests {
         let puzzle = puzzle.unwrap();
         assert_eq!(puzzle.max_words(), 5);
         assert_eq!(puzzle.sides(), sides_a);
+
+        assert!(LBPuzzle::<4, 3>::from_str(5, "erb uln imk jae").is_err());
+        assert!(LBPuzzle::<4, 3>::from_str(0, "erb uln imk jav").is_err());
+        assert!(LBPuzzle::<4, 3>::from_str(5, "erb uln imk").is_err());
+        assert!(LBPuzzle::<4, 3>::from_str(5, "erb uln imk javv").is_err());
     }
 
     #[test]
     fn test_index_side() {
-        let puzzle = LBPuzzle::<4, 3>::from_str(4, &"erb uln imk jav".to_string()).unwrap();
+        let puzzle = LBPuzzle::<4, 3>::from_str(4, "erb uln imk jav").unwrap();
         assert!(puzzle.is_idx_on_side(0, 0));
         assert!(puzzle.is_idx_on_side(3, 1));
         assert!(puzzle.is_idx_on_side(2, 0));
@@ -1,40 +1,1101 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use env_logger::Env;
 use log::debug;
-use std::env;
+use serde_json::json;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::process::ExitCode;
+use std::time::Instant;
 
 use letterboxed_solver::{
-    solvers::{a_star, SolverStrategy},
-    NYTBoxPuzzle,
+    analysis, archive,
+    batch::{self, BatchResult, BatchSummary},
+    compare, diagnosis,
+    dictionary::{self, smart_dict::SmartDictionary},
+    game::Game,
+    generator::{self, LetterFrequency, PuzzleGenerator},
+    hint::{self, Hint, HintLevel},
+    render::{Charset, Renderer},
+    solvers::{registry, SolverStrategy},
+    svg, NYTBoxPuzzle,
 };
 
-fn main() {
+/// exit codes, so scripts can tell "no" apart from "you're holding it wrong"
+const EXIT_NEGATIVE: u8 = 1; // no solution found, invalid solution, etc.
+const EXIT_BAD_INPUT: u8 = 2; // bad arguments, missing files, etc. (clap uses this too)
+
+/// Solve (and otherwise poke at) NYT Letter Boxed puzzles.
+///
+/// Puzzles are given as space-separated sides, e.g. "erb uln imk jav".
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// dictionary to use: a file in resources/dictionaries, or a path to any word list
+    #[arg(short, long, global = true, default_value = dictionary::DEFAULT_DICTIONARY)]
+    dict: String,
+
+    /// output format
+    #[arg(short, long, global = true, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// draw puzzles with plain ASCII, rather than box-drawing characters
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// human-readable
+    Text,
+    /// just the words, one per line
+    Plain,
+    /// pretty-printed JSON (see README for the schema)
+    Json,
+    /// JSON, one object per line
+    Ndjson,
+}
+
+/// bumped whenever the JSON output changes in a way that could break existing scripts
+const JSON_SCHEMA_VERSION: u32 = 2;
+
+#[derive(Subcommand)]
+enum Command {
+    /// find a solution to a puzzle
+    Solve {
+        #[command(flatten)]
+        puzzle: PuzzleArgs,
+        /// words already entered, to finish the puzzle from
+        words: Vec<String>,
+        #[command(flatten)]
+        solver: SolverArgs,
+        /// also draw the puzzle & solution to this SVG file
+        #[arg(long)]
+        svg: Option<String>,
+    },
+    /// solve many puzzles, one per line: "sides... max_words [date]"
+    Batch {
+        /// file to read puzzles from, or "-" for stdin
+        #[arg(default_value = "-")]
+        input: String,
+        #[command(flatten)]
+        solver: SolverArgs,
+    },
+    /// compare our solutions against the official answers in a puzzle archive
+    Compare {
+        /// archive file to read, or "-" for stdin (default: the bundled NYT archive)
+        archive: Option<String>,
+        #[command(flatten)]
+        solver: SolverArgs,
+    },
+    /// check whether a list of words solves a puzzle
+    Validate {
+        #[command(flatten)]
+        puzzle: PuzzleArgs,
+        /// the words of the solution, in order
+        #[arg(required = true)]
+        words: Vec<String>,
+    },
+    /// get a hint for the next word, without giving the whole answer away
+    Hint {
+        #[command(flatten)]
+        puzzle: PuzzleArgs,
+        /// the words entered so far, in order
+        words: Vec<String>,
+        /// how much to give away: 1 = first letter, 2 = + length, 3 = the whole next word
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=3))]
+        level: u8,
+    },
+    /// play a puzzle interactively: enter words one at a time (":help" for commands)
+    Play {
+        #[command(flatten)]
+        puzzle: PuzzleArgs,
+    },
+    /// generate a new, solvable puzzle
+    Generate(GenerateArgs),
+    /// find every board a known answer could have come from, e.g. "juvenile embark"
+    Construct {
+        /// the answer's words, in order, covering exactly 12 distinct letters
+        #[arg(required = true)]
+        words: Vec<String>,
+        /// the max number of words allowed in a solution
+        #[arg(short, long, default_value_t = 5)]
+        max_words: usize,
+    },
+    /// rate how hard a puzzle is (easy, medium or hard), and show why
+    Difficulty {
+        #[command(flatten)]
+        puzzle: PuzzleArgs,
+    },
+    /// check that a puzzle has exactly one solution (or at most --max-solutions)
+    Unique {
+        #[command(flatten)]
+        puzzle: PuzzleArgs,
+        /// the word count to check (default: the fewest words possible)
+        #[arg(long)]
+        words: Option<usize>,
+        /// the most solutions allowed
+        #[arg(long, default_value_t = 1)]
+        max_solutions: usize,
+    },
+    /// show statistics about a puzzle's playable words
+    Stats {
+        #[command(flatten)]
+        puzzle: PuzzleArgs,
+    },
+    /// dictionary utilities
+    #[command(subcommand)]
+    Dict(DictCommand),
+}
+
+#[derive(Subcommand)]
+enum DictCommand {
+    /// clean up a raw word list (lowercase, dedupe, drop junk) into a solver-ready dictionary
+    Compile {
+        /// the raw word list
+        input: String,
+        /// where to write the compiled dictionary
+        output: String,
+        /// drop words shorter than this
+        #[arg(long, default_value_t = 3)]
+        min_length: usize,
+    },
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    /// seed for the random number generator. The same seed & settings give the same puzzle.
+    /// (default: based on the current time)
+    #[arg(long)]
+    seed: Option<u64>,
+    /// the max number of words allowed in a solution
+    #[arg(short, long, default_value_t = 5)]
+    max_words: usize,
+    /// the fewest vowels on the board
+    #[arg(long)]
+    min_vowels: Option<usize>,
+    /// the most vowels on the board
+    #[arg(long)]
+    max_vowels: Option<usize>,
+    /// how likely each letter is to be picked
+    #[arg(long, value_enum, default_value_t = Frequency::English)]
+    frequency: Frequency,
+    /// only keep boards with at least this many two-word solutions
+    #[arg(long, default_value_t = 0)]
+    min_two_word: usize,
+    /// give up after trying this many boards
+    #[arg(long, default_value_t = 1000)]
+    attempts: usize,
+    #[command(flatten)]
+    solver: SolverArgs,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Frequency {
+    /// as often as they show up in English
+    English,
+    /// all equally likely
+    Uniform,
+}
+
+#[derive(Args)]
+struct PuzzleArgs {
+    /// the puzzle's sides, e.g. "erb uln imk jav"
+    puzzle: String,
+    /// the max number of words allowed in a solution
+    max_words: usize,
+}
+
+#[derive(Args)]
+struct SolverArgs {
+    /// solver to use, optionally with options, e.g. "astar:heuristic=lookahead"
+    #[arg(short, long, default_value = registry::DEFAULT_SOLVER)]
+    solver: String,
+    /// edge weight factor for A* solvers, between 0 and 1
+    #[arg(short, long)]
+    weight: Option<f32>,
+}
+
+/// an error message for the user, plus the exit code to go with it
+struct CliError(String, u8);
+
+type CliResult = Result<(), CliError>;
+
+fn main() -> ExitCode {
     env_logger::Builder::from_env(Env::default().default_filter_or("warn")).init();
 
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 3 {
-        eprintln!("Usage: letterboxed_solver [puzzle string] [max # of words]");
-        return;
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Command::Solve {
+            puzzle,
+            words,
+            solver,
+            svg,
+        } => solve(&cli, puzzle, words, solver, svg.as_deref()),
+        Command::Batch { input, solver } => solve_batch(&cli, input, solver),
+        Command::Compare { archive, solver } => compare(&cli, archive.as_deref(), solver),
+        Command::Validate { puzzle, words } => validate(&cli, puzzle, words),
+        Command::Hint {
+            puzzle,
+            words,
+            level,
+        } => hint(&cli, puzzle, words, *level),
+        Command::Play { puzzle } => play(&cli, puzzle),
+        Command::Generate(args) => generate(&cli, args),
+        Command::Construct { words, max_words } => construct(&cli, words, *max_words),
+        Command::Difficulty { puzzle } => difficulty(&cli, puzzle),
+        Command::Unique {
+            puzzle,
+            words,
+            max_solutions,
+        } => unique(&cli, puzzle, *words, *max_solutions),
+        Command::Stats { puzzle } => stats(&cli, puzzle),
+        Command::Dict(DictCommand::Compile {
+            input,
+            output,
+            min_length,
+        }) => dict_compile(input, output, *min_length),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(CliError(msg, code)) => {
+            if !msg.is_empty() {
+                eprintln!("{}", msg);
+            }
+            ExitCode::from(code)
+        }
     }
-    let puzz_str = args[1].clone();
-    let max_words = args[2].parse::<usize>().unwrap();
-    let puzzle = match NYTBoxPuzzle::from_str(max_words, &puzz_str) {
-        Ok(puzzle) => puzzle,
-        Err(e) => {
-            eprintln!("Invalid puzzle! Error: {:?}", e);
-            return;
+}
+
+fn load_puzzle(args: &PuzzleArgs) -> Result<NYTBoxPuzzle, CliError> {
+    let puzzle = NYTBoxPuzzle::from_str(args.max_words, &args.puzzle).map_err(|e| {
+        CliError(
+            format!("Invalid puzzle \"{}\": {}", args.puzzle, e),
+            EXIT_BAD_INPUT,
+        )
+    })?;
+    debug!("\nPUZZLE: {}", puzzle);
+    Ok(puzzle)
+}
+
+fn load_dict(cli: &Cli, puzzle: &NYTBoxPuzzle) -> Result<SmartDictionary, CliError> {
+    let reader = dictionary::try_get_dictionary_reader(&cli.dict).map_err(|e| {
+        CliError(
+            format!("Couldn't open dictionary \"{}\": {}", cli.dict, e),
+            EXIT_BAD_INPUT,
+        )
+    })?;
+    Ok(SmartDictionary::new_from_file(puzzle, reader))
+}
+
+/// the whole raw dictionary, for when it's needed for more than one puzzle
+fn load_dictionary_words(cli: &Cli) -> Result<String, CliError> {
+    let mut dictionary_words = String::new();
+    dictionary::try_get_dictionary_reader(&cli.dict)
+        .and_then(|mut r| r.read_to_string(&mut dictionary_words))
+        .map_err(|e| {
+            CliError(
+                format!("Couldn't open dictionary \"{}\": {}", cli.dict, e),
+                EXIT_BAD_INPUT,
+            )
+        })?;
+    Ok(dictionary_words)
+}
+
+/// returns the solver, along with the full spec it was built from
+fn load_solver(args: &SolverArgs) -> Result<(Box<dyn SolverStrategy<4, 3>>, String), CliError> {
+    // --weight is just shorthand for the solver's weight option
+    let spec = match args.weight {
+        Some(w) if args.solver.contains(':') => format!("{},weight={}", args.solver, w),
+        Some(w) => format!("{}:weight={}", args.solver, w),
+        None => args.solver.clone(),
+    };
+    let solver =
+        registry::get_solver(&spec).map_err(|e| CliError(e.to_string(), EXIT_BAD_INPUT))?;
+    Ok((solver, spec))
+}
+
+/// print a JSON value in whichever JSON format was asked for
+fn print_json(format: Format, value: &serde_json::Value) {
+    if format == Format::Json {
+        println!("{:#}", value);
+    } else {
+        println!("{}", value);
+    }
+}
+
+/// the sides of a puzzle as strings, e.g. ["erb", "uln", "imk", "jav"]
+fn sides_strings(puzzle: &NYTBoxPuzzle) -> Vec<String> {
+    puzzle
+        .sides()
+        .iter()
+        .map(|side| side.iter().collect())
+        .collect()
+}
+
+/// the JSON object for a single solved (or unsolved) puzzle. See README for the schema.
+fn solve_json(
+    cli: &Cli,
+    puzzle: &NYTBoxPuzzle,
+    solver_spec: &str,
+    solution: Option<&Vec<String>>,
+    elapsed_ms: f64,
+) -> serde_json::Value {
+    json!({
+        "schema_version": JSON_SCHEMA_VERSION,
+        "puzzle": sides_strings(puzzle).join(" "),
+        "sides": sides_strings(puzzle),
+        "max_words": puzzle.max_words(),
+        "solved": solution.is_some(),
+        "solution": solution,
+        "word_count": solution.map(|s| s.len()),
+        "letter_count": solution.map(|s| s.iter().map(|w| w.chars().count()).sum::<usize>()),
+        "solver": solver_spec,
+        "dictionary": cli.dict,
+        "elapsed_ms": elapsed_ms,
+    })
+}
+
+/// the JSON object explaining why a puzzle has no solution. See README for the schema.
+fn diagnosis_json(diagnosis: &diagnosis::Diagnosis) -> serde_json::Value {
+    let letters = |letters: &[char]| letters.iter().collect::<String>();
+    json!({
+        "uncoverable_letters": letters(&diagnosis.uncoverable_letters),
+        "dead_end_letters": letters(&diagnosis.dead_end_letters),
+        "last_word_only_letters": letters(&diagnosis.last_word_only_letters),
+        "nearest_miss": diagnosis.nearest_miss,
+        "missing_letters": letters(&diagnosis.missing_letters),
+        "reasons": diagnosis.reasons(),
+    })
+}
+
+fn solve(
+    cli: &Cli,
+    puzzle_args: &PuzzleArgs,
+    words: &[String],
+    solver_args: &SolverArgs,
+    svg_path: Option<&str>,
+) -> CliResult {
+    let puzzle = load_puzzle(puzzle_args)?;
+    let (solver, solver_spec) = load_solver(solver_args)?;
+    let words: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+
+    let start = Instant::now();
+    let dict = load_dict(cli, &puzzle)?;
+    let solution = solver
+        .solve_from(&puzzle, &dict, &words)
+        .map_err(|e| CliError(e.to_string(), EXIT_BAD_INPUT))?;
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    // if there's no solution, explain why not
+    let diagnosis = match solution {
+        Some(_) => None,
+        None => Some(diagnosis::diagnose(&puzzle, &dict)),
+    };
+
+    if let Format::Json | Format::Ndjson = cli.format {
+        let mut value = solve_json(cli, &puzzle, &solver_spec, solution.as_ref(), elapsed_ms);
+        value["diagnosis"] = diagnosis.as_ref().map_or(json!(null), diagnosis_json);
+        print_json(cli.format, &value);
+    }
+    if let Some(path) = svg_path {
+        // even without a solution, the board itself is worth drawing
+        std::fs::write(path, svg::to_svg(&puzzle, solution.as_ref())).map_err(|e| {
+            CliError(
+                format!("Couldn't write \"{}\": {}", path, e),
+                EXIT_BAD_INPUT,
+            )
+        })?;
+    }
+    let Some(solution) = solution else {
+        let mut msg = "No solution found! :(".to_string();
+        let reasons = diagnosis.map(|d| d.reasons()).unwrap_or_default();
+        if reasons.is_empty() && !words.is_empty() {
+            msg += "\nThe puzzle can be solved, just not starting with those words.";
         }
+        for reason in reasons {
+            msg += &format!("\n- {}", reason);
+        }
+        return Err(CliError(msg, EXIT_NEGATIVE));
     };
-    debug!("\nPUZZLE: {}", puzzle);
+    match cli.format {
+        Format::Text => {
+            println!("\nPUZZLE: {}", puzzle);
+            println!("SOLUTION: {:?}\n", solution);
+            let drawing = renderer(cli)
+                .with_scale(2)
+                .render_solution(&puzzle, &solution);
+            print!("{}", drawing);
+        }
+        Format::Plain => solution.iter().for_each(|w| println!("{}", w)),
+        Format::Json | Format::Ndjson => {}
+    }
+    Ok(())
+}
+
+fn solve_batch(cli: &Cli, input: &str, solver_args: &SolverArgs) -> CliResult {
+    let io_error = |e: io::Error| CliError(e.to_string(), EXIT_BAD_INPUT);
+    let (solver, solver_spec) = load_solver(solver_args)?;
+
+    // read the whole dictionary once, rather than re-reading the file for every puzzle
+    let dictionary_words = load_dictionary_words(cli)?;
+    let reader: Box<dyn BufRead> = if input == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(input).map_err(io_error)?))
+    };
+
+    let mut json_results = Vec::new();
+    let summary = batch::solve_batch(reader, &dictionary_words, solver.as_ref(), |result| {
+        let value = match result {
+            BatchResult::Attempted {
+                entry,
+                solution,
+                elapsed,
+            } => {
+                if cli.format == Format::Text {
+                    let date = entry.date.as_deref().unwrap_or("-");
+                    match solution {
+                        Some(s) => println!("{} {}: {:?}", date, entry.puzzle, s),
+                        None => println!("{} {}: NO SOLUTION", date, entry.puzzle),
+                    }
+                }
+                let mut value = solve_json(
+                    cli,
+                    &entry.puzzle,
+                    &solver_spec,
+                    solution.as_ref(),
+                    elapsed.as_secs_f64() * 1000.0,
+                );
+                value["line"] = json!(entry.line);
+                value["date"] = json!(entry.date);
+                value
+            }
+            BatchResult::Invalid { line, text, error } => {
+                if cli.format == Format::Text {
+                    eprintln!("line {}: invalid puzzle \"{}\": {}", line, text, error);
+                }
+                json!({
+                    "schema_version": JSON_SCHEMA_VERSION,
+                    "line": line,
+                    "input": text,
+                    "error": error,
+                })
+            }
+        };
+        match cli.format {
+            Format::Ndjson => print_json(cli.format, &value),
+            Format::Json => json_results.push(value),
+            Format::Text | Format::Plain => {}
+        }
+    })
+    .map_err(io_error)?;
+
+    let summary_value = batch_summary_json(&summary);
+    match cli.format {
+        Format::Text => {
+            println!(
+                "\nSOLVED: {} | UNSOLVED: {} | INVALID: {}",
+                summary.solved, summary.unsolved, summary.invalid
+            );
+            if let Some(avg) = summary.average_words() {
+                println!("AVERAGE WORDS: {:.2}", avg);
+            }
+            println!(
+                "TOTAL TIME: {:.1}ms",
+                summary.total_time.as_secs_f64() * 1000.0
+            );
+        }
+        Format::Plain => println!(
+            "{} {} {}",
+            summary.solved, summary.unsolved, summary.invalid
+        ),
+        Format::Json => print_json(
+            cli.format,
+            &json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "results": json_results,
+                "summary": summary_value,
+            }),
+        ),
+        Format::Ndjson => print_json(
+            cli.format,
+            &json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "summary": summary_value,
+            }),
+        ),
+    }
+
+    if summary.unsolved > 0 || summary.invalid > 0 {
+        return Err(CliError(String::new(), EXIT_NEGATIVE));
+    }
+    Ok(())
+}
+
+fn compare(cli: &Cli, archive_path: Option<&str>, solver_args: &SolverArgs) -> CliResult {
+    let (solver, solver_spec) = load_solver(solver_args)?;
+    let dictionary_words = load_dictionary_words(cli)?;
+    let entries = match archive_path {
+        None => archive::load_nyt_archive(),
+        Some("-") => archive::read_archive(io::stdin().lock()),
+        Some(path) => File::open(path)
+            .map_err(|e| CliError(e.to_string(), EXIT_BAD_INPUT))
+            .map(|f| archive::read_archive(BufReader::new(f)))?,
+    }
+    .map_err(|e| CliError(e.to_string(), EXIT_BAD_INPUT))?;
+
+    let mut json_results = Vec::new();
+    let summary =
+        compare::compare_with_answers(&entries, &dictionary_words, solver.as_ref(), |comparison| {
+            let value = json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "date": comparison.date,
+                "official": comparison.official,
+                "ours": comparison.ours,
+                "official_reachable": comparison.official_reachable(),
+                "as_short": comparison.as_short(),
+                "missing_words": comparison.missing_words,
+            });
+            match cli.format {
+                Format::Text => {
+                    let ours = match &comparison.ours {
+                        Some(ours) => format!("{:?}", ours),
+                        None => "NO SOLUTION".to_string(),
+                    };
+                    println!(
+                        "{}: official {:?}, ours {}{}",
+                        comparison.date,
+                        comparison.official,
+                        ours,
+                        if comparison.as_short() {
+                            ""
+                        } else {
+                            " (longer!)"
+                        }
+                    );
+                    if !comparison.official_reachable() {
+                        println!("  missing: {}", comparison.missing_words.join(" "));
+                    }
+                }
+                // the missing words, one per line, ready to add to a dictionary
+                Format::Plain => comparison
+                    .missing_words
+                    .iter()
+                    .for_each(|w| println!("{}", w)),
+                Format::Ndjson => print_json(cli.format, &value),
+                Format::Json => json_results.push(value),
+            }
+        });
 
-    // solve!
-    let solver = a_star::AStarSolver::new(1.0);
-    let solution = match solver.solve(&puzzle) {
-        Some(solution) => solution,
-        None => {
-            eprintln!("No solution found! :(");
-            return;
+    let summary_value = json!({
+        "compared": summary.compared,
+        "reachable": summary.reachable,
+        "as_short": summary.as_short,
+        "missing_words": summary.missing_words,
+        "solver": solver_spec,
+        "dictionary": cli.dict,
+    });
+    match cli.format {
+        Format::Text => {
+            println!(
+                "\nCOMPARED: {} | REACHABLE: {} | AS SHORT: {}",
+                summary.compared, summary.reachable, summary.as_short
+            );
+            if !summary.missing_words.is_empty() {
+                let missing: Vec<&str> = summary.missing_words.iter().map(|w| w.as_str()).collect();
+                println!("MISSING WORDS: {}", missing.join(" "));
+            }
         }
+        Format::Plain => {}
+        Format::Json => print_json(
+            cli.format,
+            &json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "results": json_results,
+                "summary": summary_value,
+            }),
+        ),
+        Format::Ndjson => print_json(
+            cli.format,
+            &json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "summary": summary_value,
+            }),
+        ),
+    }
+    Ok(())
+}
+
+fn batch_summary_json(summary: &BatchSummary) -> serde_json::Value {
+    json!({
+        "solved": summary.solved,
+        "unsolved": summary.unsolved,
+        "invalid": summary.invalid,
+        "average_words": summary.average_words(),
+        "total_ms": summary.total_time.as_secs_f64() * 1000.0,
+    })
+}
+
+fn validate(cli: &Cli, puzzle_args: &PuzzleArgs, words: &[String]) -> CliResult {
+    let puzzle = load_puzzle(puzzle_args)?;
+    let dict = load_dict(cli, &puzzle)?;
+    let words: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+
+    let report = puzzle.check_solution(&words, Some(&dict));
+    let valid = report.is_valid();
+    match cli.format {
+        Format::Text if valid => println!("VALID: {:?}", words),
+        Format::Text => {
+            println!("INVALID: {:?}", words);
+            report.problems.iter().for_each(|p| println!("  - {}", p));
+        }
+        Format::Plain => println!("{}", if valid { "valid" } else { "invalid" }),
+        Format::Json | Format::Ndjson => print_json(
+            cli.format,
+            &json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "puzzle": sides_strings(&puzzle).join(" "),
+                "words": words,
+                "valid": valid,
+                "problems": report.problems,
+            }),
+        ),
+    }
+    if !valid {
+        return Err(CliError(String::new(), EXIT_NEGATIVE));
+    }
+    Ok(())
+}
+
+fn hint(cli: &Cli, puzzle_args: &PuzzleArgs, words: &[String], level: u8) -> CliResult {
+    let puzzle = load_puzzle(puzzle_args)?;
+    let dict = load_dict(cli, &puzzle)?;
+    let words: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+    let level = match level {
+        1 => HintLevel::FirstLetter,
+        2 => HintLevel::Length,
+        _ => HintLevel::Word,
     };
-    println!("\nPUZZLE: {}", puzzle);
-    println!("SOLUTION: {:?}", solution);
+
+    let hint = hint::next_word_hint(&puzzle, &dict, &words, level)
+        .map_err(|e| CliError(e.to_string(), EXIT_BAD_INPUT))?
+        .ok_or(CliError(
+            "No hint available: the puzzle is already solved, or can't be finished from here."
+                .to_string(),
+            EXIT_NEGATIVE,
+        ))?;
+    match cli.format {
+        Format::Text => println!(
+            "HINT: {} ({} more words to finish)",
+            hint, hint.words_to_finish
+        ),
+        Format::Plain => println!("{}", hint),
+        Format::Json | Format::Ndjson => print_json(
+            cli.format,
+            &json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "puzzle": sides_strings(&puzzle).join(" "),
+                "words": words,
+                "level": level as u8 + 1,
+                "hint": hint.to_string(),
+                "word_count": words.len() + hint.words_to_finish,
+            }),
+        ),
+    }
+    Ok(())
+}
+
+const PLAY_HELP: &str = "Enter one word at a time. Commands:
+  :undo   take back the last word
+  :hint   get a hint for the next word (repeat for more)
+  :help   show this message
+  :quit   give up";
+
+/// the interactive game. this is text-only, so it ignores --format.
+fn play(cli: &Cli, puzzle_args: &PuzzleArgs) -> CliResult {
+    let puzzle = load_puzzle(puzzle_args)?;
+    let dict = load_dict(cli, &puzzle)?;
+    let mut game = Game::new(&puzzle, &dict);
+    // the hint being revealed, if any. it's only good until the words change.
+    let mut hint: Option<Hint> = None;
+
+    println!("{}\n", PLAY_HELP);
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print_game(cli, &game);
+        if game.is_won() {
+            println!("SOLVED in {} words: {:?}", game.words().len(), game.words());
+            return Ok(());
+        }
+        if game.is_lost() {
+            return Err(CliError(
+                "Out of words! Better luck next time.".to_string(),
+                EXIT_NEGATIVE,
+            ));
+        }
+
+        print!("> ");
+        io::stdout().flush().ok();
+        let line = match lines.next() {
+            Some(line) => line.map_err(|e| CliError(e.to_string(), EXIT_BAD_INPUT))?,
+            None => return Err(CliError(String::new(), EXIT_NEGATIVE)),
+        };
+        match line.trim() {
+            "" => continue,
+            ":quit" | ":q" => return Err(CliError("Gave up.".to_string(), EXIT_NEGATIVE)),
+            ":help" | ":h" => println!("{}", PLAY_HELP),
+            ":undo" | ":u" => match game.undo() {
+                Some(word) => {
+                    println!("Took back \"{}\"", word);
+                    hint = None;
+                }
+                None => println!("Nothing to undo."),
+            },
+            ":hint" => {
+                let revealed = match hint.as_mut() {
+                    Some(h) => h.reveal_more(),
+                    None => {
+                        hint = game
+                            .hint(HintLevel::FirstLetter)
+                            .map_err(|e| CliError(e.to_string(), EXIT_BAD_INPUT))?;
+                        true
+                    }
+                };
+                match &hint {
+                    Some(h) if revealed => {
+                        println!("HINT: {} ({} more words to finish)", h, h.words_to_finish)
+                    }
+                    Some(h) => println!("That's the whole hint: {}", h),
+                    None => println!("No hint available: can't finish from here. Try :undo."),
+                }
+            }
+            command if command.starts_with(':') => {
+                println!("Unknown command \"{}\"\n{}", command, PLAY_HELP)
+            }
+            word => match game.play_word(word) {
+                Ok(()) => hint = None,
+                Err(e) => println!("Nope! {}", e),
+            },
+        }
+    }
+}
+
+/// the board (used letters in uppercase) & the words so far
+fn print_game(cli: &Cli, game: &Game<4, 3>) {
+    println!(
+        "\n{}",
+        renderer(cli).render_box(game.puzzle(), &game.covered())
+    );
+    if !game.words().is_empty() {
+        println!("WORDS: {}", game.words().join(" - "));
+    }
+    println!(
+        "{} words left{}",
+        game.words_left(),
+        match game.next_letter() {
+            Some(c) => format!(", next word starts with \"{}\"", c),
+            None => String::new(),
+        }
+    );
+}
+
+fn renderer(cli: &Cli) -> Renderer {
+    Renderer::new(match cli.ascii {
+        true => Charset::Ascii,
+        false => Charset::Unicode,
+    })
+}
+
+fn generate(cli: &Cli, args: &GenerateArgs) -> CliResult {
+    let dictionary_words = load_dictionary_words(cli)?;
+    let (solver, solver_spec) = load_solver(&args.solver)?;
+    let seed = args.seed.unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+
+    let mut generator = PuzzleGenerator::new(&dictionary_words)
+        .with_seed(seed)
+        .with_max_words(args.max_words)
+        .with_letter_frequency(match args.frequency {
+            Frequency::English => LetterFrequency::English,
+            Frequency::Uniform => LetterFrequency::Uniform,
+        })
+        .with_min_two_word_solutions(args.min_two_word)
+        .with_max_attempts(args.attempts)
+        .with_solver(solver);
+    if args.min_vowels.is_some() || args.max_vowels.is_some() {
+        generator = generator.with_vowels(
+            args.min_vowels.unwrap_or(0),
+            args.max_vowels.unwrap_or(NYTBoxPuzzle::n_letters()),
+        );
+    }
+    generator
+        .check_config()
+        .map_err(|e| CliError(e.to_string(), EXIT_BAD_INPUT))?;
+    let generated = generator
+        .generate()
+        .map_err(|e| CliError(e.to_string(), EXIT_NEGATIVE))?;
+
+    let puzzle = &generated.puzzle;
+    match cli.format {
+        Format::Text => {
+            println!("\nPUZZLE: {}", puzzle);
+            println!("SEED: {}", seed);
+            println!("EXAMPLE SOLUTION: {:?}", generated.solution);
+            println!("TWO-WORD SOLUTIONS: {}", generated.two_word_solutions);
+        }
+        // same as a line of batch input
+        Format::Plain => println!("{} {}", sides_strings(puzzle).join(" "), puzzle.max_words()),
+        Format::Json | Format::Ndjson => print_json(
+            cli.format,
+            &json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "puzzle": sides_strings(puzzle).join(" "),
+                "sides": sides_strings(puzzle),
+                "max_words": puzzle.max_words(),
+                "seed": seed,
+                "solution": generated.solution,
+                "two_word_solutions": generated.two_word_solutions,
+                "attempts": generated.attempts,
+                "solver": solver_spec,
+                "dictionary": cli.dict,
+            }),
+        ),
+    }
+    Ok(())
+}
+
+fn construct(cli: &Cli, words: &[String], max_words: usize) -> CliResult {
+    let seed_words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+    let puzzles: Vec<NYTBoxPuzzle> = generator::construct_from_words(&seed_words, max_words)
+        .map_err(|e| CliError(e.to_string(), EXIT_BAD_INPUT))?;
+
+    match cli.format {
+        Format::Text => {
+            println!("\nANSWER: {:?}", words);
+            println!("BOARDS: {}", puzzles.len());
+            for puzzle in &puzzles {
+                println!("  {}", sides_strings(puzzle).join(" "));
+            }
+        }
+        // same as lines of batch input
+        Format::Plain => {
+            for puzzle in &puzzles {
+                println!("{} {}", sides_strings(puzzle).join(" "), puzzle.max_words());
+            }
+        }
+        Format::Json | Format::Ndjson => print_json(
+            cli.format,
+            &json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "words": words,
+                "max_words": max_words,
+                "puzzles": puzzles.iter().map(|p| sides_strings(p).join(" ")).collect::<Vec<_>>(),
+            }),
+        ),
+    }
+    match puzzles.is_empty() {
+        true => Err(CliError(String::new(), EXIT_NEGATIVE)),
+        false => Ok(()),
+    }
+}
+
+fn difficulty(cli: &Cli, puzzle_args: &PuzzleArgs) -> CliResult {
+    let puzzle = load_puzzle(puzzle_args)?;
+    let dict = load_dict(cli, &puzzle)?;
+    let rating = analysis::rate_difficulty(&puzzle, &dict);
+    let features = &rating.features;
+
+    match cli.format {
+        Format::Text => {
+            println!("\nPUZZLE: {}", puzzle);
+            println!(
+                "DIFFICULTY: {} ({:.0}/100)",
+                rating.difficulty, rating.score
+            );
+            println!("  playable words: {}", features.playable_words);
+            match features.optimal_words {
+                Some(words) => println!(
+                    "  optimal solutions: {}{} with {} words",
+                    features.optimal_solutions,
+                    if features.optimal_solutions >= analysis::SOLUTION_CAP {
+                        "+"
+                    } else {
+                        ""
+                    },
+                    words
+                ),
+                None => println!("  optimal solutions: none"),
+            }
+            if let (Some(solution), Some(rank)) =
+                (&features.easiest_solution, features.easiest_solution_rank)
+            {
+                println!(
+                    "  easiest solution: {:?} (rarest word rank {})",
+                    solution, rank
+                );
+            }
+            println!(
+                "  letters only in rare words: {}",
+                features.rare_only_letters.iter().collect::<String>()
+            );
+            println!("  branching factor: {:.1}", features.branching_factor);
+        }
+        Format::Plain => println!("{}", rating.difficulty),
+        Format::Json | Format::Ndjson => print_json(
+            cli.format,
+            &json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "puzzle": sides_strings(&puzzle).join(" "),
+                "difficulty": rating.difficulty.to_string(),
+                "score": rating.score,
+                "playable_words": features.playable_words,
+                "optimal_words": features.optimal_words,
+                "optimal_solutions": features.optimal_solutions,
+                "easiest_solution": features.easiest_solution,
+                "easiest_solution_rank": features.easiest_solution_rank,
+                "rare_only_letters": features.rare_only_letters.iter().collect::<String>(),
+                "branching_factor": features.branching_factor,
+            }),
+        ),
+    }
+    Ok(())
+}
+
+fn unique(
+    cli: &Cli,
+    puzzle_args: &PuzzleArgs,
+    n_words: Option<usize>,
+    max_solutions: usize,
+) -> CliResult {
+    let puzzle = load_puzzle(puzzle_args)?;
+    let dict = load_dict(cli, &puzzle)?;
+    let count = analysis::count_solutions(&puzzle, &dict, n_words, max_solutions);
+    let too_many = count.solutions.len() > max_solutions;
+
+    match cli.format {
+        Format::Text => {
+            println!("\nPUZZLE: {}", puzzle);
+            match count.n_words {
+                Some(n_words) if !count.solutions.is_empty() => println!(
+                    "SOLUTIONS WITH {} WORDS: {}{}",
+                    n_words,
+                    count.solutions.len(),
+                    if too_many { " (or more)" } else { "" }
+                ),
+                _ => println!("NO SOLUTIONS"),
+            }
+            for solution in &count.solutions {
+                println!("  {}", solution.join(" "));
+            }
+        }
+        Format::Plain => {
+            for solution in &count.solutions {
+                println!("{}", solution.join(" "));
+            }
+        }
+        Format::Json | Format::Ndjson => print_json(
+            cli.format,
+            &json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "puzzle": sides_strings(&puzzle).join(" "),
+                "word_count": count.n_words,
+                "max_solutions": max_solutions,
+                "solutions": count.solutions,
+                "unique": count.is_unique(),
+                "within_limit": count.is_within_limit(),
+            }),
+        ),
+    }
+    match count.is_within_limit() {
+        true => Ok(()),
+        false => Err(CliError(String::new(), EXIT_NEGATIVE)),
+    }
+}
+
+fn stats(cli: &Cli, puzzle_args: &PuzzleArgs) -> CliResult {
+    let puzzle = load_puzzle(puzzle_args)?;
+    let dict = load_dict(cli, &puzzle)?;
+    let stats = dict.stats();
+    let sides = sides_strings(&puzzle);
+
+    match cli.format {
+        Format::Text => {
+            println!("\nPUZZLE: {}", puzzle);
+            println!(
+                "PLAYABLE WORDS: {} (of {} lines)",
+                stats.playable_words, stats.lines_read
+            );
+            for (length, count) in &stats.by_length {
+                println!("  {:>2} letters: {}", length, count);
+            }
+            if let Some(word) = &stats.longest_word {
+                println!("LONGEST WORD: {}", word);
+            }
+            if let Some((word, n)) = &stats.max_coverage {
+                println!("MOST LETTERS IN ONE WORD: {} ({})", word, n);
+            }
+            println!("LETTERS: contains / starts / ends");
+            for (letter, counts) in &stats.letters {
+                println!(
+                    "  {}: {:>4} / {:>4} / {:>4}",
+                    letter, counts.contains, counts.starts, counts.ends
+                );
+            }
+            println!("SIDE TRANSITIONS: from (rows) -> to (columns)");
+            println!(
+                "  {:>5} {}",
+                "",
+                sides
+                    .iter()
+                    .map(|s| format!("{:>5}", s))
+                    .collect::<String>()
+            );
+            for (side, transitions) in sides.iter().zip(&stats.side_transitions) {
+                let row: String = transitions.iter().map(|n| format!("{:>5}", n)).collect();
+                println!("  {:>5} {}", side, row);
+            }
+        }
+        Format::Plain => println!("{}", stats.playable_words),
+        Format::Json | Format::Ndjson => print_json(
+            cli.format,
+            &json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "puzzle": sides.join(" "),
+                "playable_words": stats.playable_words,
+                "lines_read": stats.lines_read,
+                "by_length": stats.by_length,
+                "letters": stats.letters.iter().map(|(letter, counts)| (letter.to_string(), json!({
+                    "contains": counts.contains,
+                    "starts": counts.starts,
+                    "ends": counts.ends,
+                }))).collect::<serde_json::Map<_, _>>(),
+                "side_transitions": stats.side_transitions,
+                "longest_word": stats.longest_word,
+                "max_coverage": stats.max_coverage.as_ref().map(|(word, n)| json!({
+                    "word": word,
+                    "letters": n,
+                })),
+            }),
+        ),
+    }
+    Ok(())
+}
+
+fn dict_compile(input: &str, output: &str, min_length: usize) -> CliResult {
+    let io_error = |e: std::io::Error| CliError(format!("{}", e), EXIT_BAD_INPUT);
+    let reader = dictionary::try_get_dictionary_reader(input).map_err(io_error)?;
+    let writer = BufWriter::new(File::create(output).map_err(io_error)?);
+
+    let (n_lines, n_words) =
+        dictionary::compile_dictionary(reader, writer, min_length).map_err(io_error)?;
+    eprintln!("Compiled {} words from {} lines.", n_words, n_lines);
+    Ok(())
 }
@@ -0,0 +1,554 @@
+//! Draws a puzzle as text: its box (or other polygon, for S != 4) with the letters around the
+//! edges, and optionally a solution as lines hopping from side to side.
+//!
+//! Letters that have been used are drawn in uppercase. The drawing is fully deterministic, so it's
+//! safe to use for test snapshots.
+//!
+//! ```text
+//!       E   r   B
+//!   ┌───────────────┐
+//!   │   ∘∘      ·   │
+//! j │     ∘∘     ·· │ U
+//!   │       ∘∘   ·  │
+//! A │ ∘∘∘     ∘∘·   │ l
+//!   │   ∘∘∘∘∘∘∘·∘∘  │
+//! v │     ∘∘ · ∘∘∘∘ │ N
+//!   │       ∘       │
+//!   └───────────────┘
+//!       i   M   k
+//! ```
+//!
+//! (that's "bum", then "mane")
+//!
+//! Under the hood, the polygon's corners sit on a circle, with side 0 on top and the rest going
+//! clockwise. Everything is computed in "row units", then squashed onto a grid of characters which
+//! are about twice as tall as they are wide.
+
+use crate::{LBPuzzle, LBPuzzleSolution};
+use std::collections::BTreeSet;
+use std::f64::consts::PI;
+
+/// which characters to draw with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Charset {
+    /// box-drawing characters
+    #[default]
+    Unicode,
+    /// plain ASCII, for terminals that can't do better
+    Ascii,
+}
+
+impl Charset {
+    fn horizontal(self) -> char {
+        match self {
+            Charset::Unicode => '─',
+            Charset::Ascii => '-',
+        }
+    }
+
+    fn vertical(self) -> char {
+        match self {
+            Charset::Unicode => '│',
+            Charset::Ascii => '|',
+        }
+    }
+
+    /// a line going down & to the right
+    fn falling(self) -> char {
+        match self {
+            Charset::Unicode => '╲',
+            Charset::Ascii => '\\',
+        }
+    }
+
+    /// a line going up & to the right
+    fn rising(self) -> char {
+        match self {
+            Charset::Unicode => '╱',
+            Charset::Ascii => '/',
+        }
+    }
+
+    /// hops are drawn with a different character for each word, cycling through these
+    fn hop(self, word: usize) -> char {
+        let hops = match self {
+            Charset::Unicode => ['·', '∘', '×', '*', '+'],
+            Charset::Ascii => ['.', 'o', 'x', '*', '+'],
+        };
+        hops[word % hops.len()]
+    }
+
+    /// a polygon corner, where "right" & "down" say which way its two edges go
+    fn corner(self, right: Option<bool>, down: Option<bool>) -> char {
+        match (self, right, down) {
+            (Charset::Unicode, Some(true), Some(true)) => '┌',
+            (Charset::Unicode, Some(false), Some(true)) => '┐',
+            (Charset::Unicode, Some(true), Some(false)) => '└',
+            (Charset::Unicode, Some(false), Some(false)) => '┘',
+            (Charset::Unicode, _, _) => '•',
+            (Charset::Ascii, _, _) => '+',
+        }
+    }
+}
+
+/// draws puzzles & solutions, per the module docs
+#[derive(Debug, Clone, Copy)]
+pub struct Renderer {
+    charset: Charset,
+    scale: usize,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self::new(Charset::default())
+    }
+}
+
+/// a point, centered on the middle of the polygon. x goes right & y goes down.
+/// (also used by the svg module, in pixels rather than row units)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct _Point {
+    pub(crate) x: f64,
+    pub(crate) y: f64,
+}
+
+/// where everything goes, before it's drawn
+pub(crate) struct _Layout {
+    pub(crate) corners: Vec<_Point>,
+    // per side, per letter: where the letter's drawn
+    pub(crate) labels: Vec<Vec<_Point>>,
+    // per side, per letter: where hops to & from the letter start
+    pub(crate) anchors: Vec<Vec<_Point>>,
+}
+
+impl Renderer {
+    pub fn new(charset: Charset) -> Self {
+        Self { charset, scale: 1 }
+    }
+
+    /// Draw everything "scale" times bigger (at least 1). Handy for solutions, whose hops get
+    /// crowded at the default size.
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Draw the puzzle, with the letters in "covered" marked as used.
+    pub fn render_box<const S: usize, const L: usize>(
+        &self,
+        puzzle: &LBPuzzle<S, L>,
+        covered: &BTreeSet<char>,
+    ) -> String {
+        let layout = _Layout::for_text::<S, L>(self.scale);
+        let mut canvas = _Canvas::new(&layout);
+        self.draw_box(&mut canvas, puzzle, &layout, covered);
+        canvas.to_string()
+    }
+
+    /// Draw the puzzle with a solution (or partial solution) on top: used letters are marked, and
+    /// every hop from letter to letter is drawn as a dotted line, with different dots for each word.
+    /// Drawing stops at the first hop which isn't possible on this board.
+    pub fn render_solution<const S: usize, const L: usize>(
+        &self,
+        puzzle: &LBPuzzle<S, L>,
+        solution: &LBPuzzleSolution,
+    ) -> String {
+        let layout = _Layout::for_text::<S, L>(self.scale);
+        let mut canvas = _Canvas::new(&layout);
+        let covered: BTreeSet<char> = solution.iter().flat_map(|w| w.chars()).collect();
+
+        // draw the hops first, so the box & letters always win where they overlap
+        // a word with n letters makes n - 1 hops
+        let hop_words = solution
+            .iter()
+            .enumerate()
+            .flat_map(|(i, w)| std::iter::repeat_n(i, w.chars().count().saturating_sub(1)));
+        let positions = _letter_positions(puzzle, solution);
+        for (hop, word) in positions.windows(2).zip(hop_words) {
+            let (from, to) = (hop[0], hop[1]);
+            let from = layout.anchors[from.0][from.1];
+            let to = layout.anchors[to.0][to.1];
+            canvas.line(from, to, |_| self.charset.hop(word));
+        }
+        self.draw_box(&mut canvas, puzzle, &layout, &covered);
+        canvas.to_string()
+    }
+
+    fn draw_box<const S: usize, const L: usize>(
+        &self,
+        canvas: &mut _Canvas,
+        puzzle: &LBPuzzle<S, L>,
+        layout: &_Layout,
+        covered: &BTreeSet<char>,
+    ) {
+        let n = layout.corners.len();
+        for i in 0..n {
+            let (a, b) = (layout.corners[i], layout.corners[(i + 1) % n]);
+            let c = self.edge_char(canvas, a, b);
+            if c == self.charset.falling() || c == self.charset.rising() {
+                // a run of slashes on one row looks like a staircase, so one per row
+                canvas.diagonal(a, b, c);
+            } else {
+                canvas.line(a, b, |_| c);
+            }
+        }
+        for i in 0..n {
+            let prev = layout.corners[(i + n - 1) % n];
+            let next = layout.corners[(i + 1) % n];
+            let corner = layout.corners[i];
+            let c = self.corner_char(canvas, corner, prev, next);
+            canvas.set(corner, c);
+        }
+
+        for (side, labels) in puzzle.sides().iter().zip(&layout.labels) {
+            for (letter, &at) in side.iter().zip(labels) {
+                let shown = match covered.contains(letter) {
+                    true => letter.to_uppercase().next().unwrap_or(*letter),
+                    false => *letter,
+                };
+                canvas.set(at, shown);
+            }
+        }
+    }
+
+    /// pick a line character based on the edge's slope on the grid
+    fn edge_char(&self, canvas: &_Canvas, a: _Point, b: _Point) -> char {
+        let ((ax, ay), (bx, by)) = (canvas.cell(a), canvas.cell(b));
+        let (dx, dy) = ((bx - ax) as f64, (by - ay) as f64);
+        if dy.abs() < dx.abs() * 0.25 {
+            self.charset.horizontal()
+        } else if dx.abs() < dy.abs() * 0.25 {
+            self.charset.vertical()
+        } else if (dx > 0.0) == (dy > 0.0) {
+            self.charset.falling()
+        } else {
+            self.charset.rising()
+        }
+    }
+
+    /// box corners only make sense where a horizontal edge meets a vertical one
+    fn corner_char(&self, canvas: &_Canvas, corner: _Point, prev: _Point, next: _Point) -> char {
+        let (cx, cy) = canvas.cell(corner);
+        let (mut right, mut down) = (None, None);
+        for other in [prev, next] {
+            let (ox, oy) = canvas.cell(other);
+            if oy == cy {
+                right = Some(ox > cx);
+            } else if ox == cx {
+                down = Some(oy > cy);
+            }
+        }
+        self.charset.corner(right, down)
+    }
+}
+
+impl _Layout {
+    /// in row units, with labels just outside the edges & anchors just inside
+    fn for_text<const S: usize, const L: usize>(scale: usize) -> Self {
+        // big enough that neighbouring letters on the shortest (i.e. vertical) edges of a square
+        // are 2 rows apart
+        let radius = 1.5 * (L + 1) as f64 * scale as f64;
+        let mut layout = Self::new::<S, L>(radius, 0.0, -1.0);
+
+        // labels go a row above/below horizontal edges, and a bit to the side of everything else.
+        // (pushing them along the normal would leave them touching slanted edges, since a row
+        // is only 1 unit tall but the edge can cross it anywhere within 2 columns)
+        for (i, labels) in layout.labels.iter_mut().enumerate() {
+            let (a, b) = (layout.corners[i], layout.corners[(i + 1) % S]);
+            let (mid_x, mid_y) = ((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+            for label in labels {
+                if (b.y - a.y).abs() < 1e-9 {
+                    label.y += mid_y.signum();
+                } else if (b.x - a.x).abs() < 1e-9 {
+                    label.x += mid_x.signum();
+                } else {
+                    label.x += 1.5 * mid_x.signum();
+                }
+            }
+        }
+        layout
+    }
+
+    /// "radius" is the distance from the center to each corner. Labels & anchors are pushed
+    /// outwards from the edge by their offsets (so a negative offset is inside the polygon).
+    pub(crate) fn new<const S: usize, const L: usize>(
+        radius: f64,
+        label_offset: f64,
+        anchor_offset: f64,
+    ) -> Self {
+        let corners: Vec<_Point> = (0..S)
+            .map(|i| {
+                // side 0 on top, going clockwise (y is down, so that's increasing angle)
+                let angle = -PI / 2.0 - PI / S as f64 + 2.0 * PI * i as f64 / S as f64;
+                _Point {
+                    x: radius * angle.cos(),
+                    y: radius * angle.sin(),
+                }
+            })
+            .collect();
+
+        let mut labels = Vec::new();
+        let mut anchors = Vec::new();
+        for i in 0..S {
+            let (mut a, mut b) = (corners[i], corners[(i + 1) % S]);
+            // going clockwise, some sides run backwards. flip them so every side's letters read
+            // top to bottom, or left to right for horizontal sides.
+            let horizontal = (b.y - a.y).abs() < 1e-9;
+            if (horizontal && b.x < a.x) || (!horizontal && b.y < a.y) {
+                (a, b) = (b, a);
+            }
+            // outward normal, i.e. pointing from the center through the edge's midpoint
+            let angle = -PI / 2.0 + 2.0 * PI * i as f64 / S as f64;
+            let (nx, ny) = (angle.cos(), angle.sin());
+
+            let on_edge = |j: usize| {
+                let t = (j + 1) as f64 / (L + 1) as f64;
+                (a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+            };
+            labels.push(
+                (0..L)
+                    .map(|j| {
+                        let (x, y) = on_edge(j);
+                        _Point {
+                            x: x + nx * label_offset,
+                            y: y + ny * label_offset,
+                        }
+                    })
+                    .collect(),
+            );
+            anchors.push(
+                (0..L)
+                    .map(|j| {
+                        let (x, y) = on_edge(j);
+                        _Point {
+                            x: x + nx * anchor_offset,
+                            y: y + ny * anchor_offset,
+                        }
+                    })
+                    .collect(),
+            );
+        }
+
+        Self {
+            corners,
+            labels,
+            anchors,
+        }
+    }
+}
+
+/// a grid of characters, big enough for a layout
+struct _Canvas {
+    cells: Vec<Vec<char>>,
+    // the grid cell of the point (0, 0)
+    origin: (i64, i64),
+}
+
+impl _Canvas {
+    fn new(layout: &_Layout) -> Self {
+        let points = layout.corners.iter().chain(layout.labels.iter().flatten());
+        let (mut min, mut max) = ((i64::MAX, i64::MAX), (i64::MIN, i64::MIN));
+        for p in points {
+            let (x, y) = _to_cell(*p);
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+        let (width, height) = ((max.0 - min.0 + 1) as usize, (max.1 - min.1 + 1) as usize);
+        Self {
+            cells: vec![vec![' '; width]; height],
+            origin: (-min.0, -min.1),
+        }
+    }
+
+    /// the (column, row) of a point on the grid
+    fn cell(&self, p: _Point) -> (i64, i64) {
+        let (x, y) = _to_cell(p);
+        (x + self.origin.0, y + self.origin.1)
+    }
+
+    fn set(&mut self, p: _Point, c: char) {
+        let (x, y) = self.cell(p);
+        self.set_cell(x, y, c);
+    }
+
+    /// anything off the grid is just dropped
+    fn set_cell(&mut self, x: i64, y: i64, c: char) {
+        if let Some(cell) = self
+            .cells
+            .get_mut(y as usize)
+            .and_then(|r| r.get_mut(x as usize))
+        {
+            *cell = c;
+        }
+    }
+
+    /// draw a steep or diagonal line from a to b, with one character per row
+    fn diagonal(&mut self, a: _Point, b: _Point, c: char) {
+        let ((ax, ay), (bx, by)) = (self.cell(a), self.cell(b));
+        let steps = (by - ay).abs().max(1);
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            let x = ax + ((bx - ax) as f64 * t).round() as i64;
+            let y = ay + ((by - ay) as f64 * t).round() as i64;
+            self.set_cell(x, y, c);
+        }
+    }
+
+    /// draw a straight line from a to b. "c" picks the character for each cell, given what's there.
+    fn line(&mut self, a: _Point, b: _Point, c: impl Fn(char) -> char) {
+        let ((ax, ay), (bx, by)) = (self.cell(a), self.cell(b));
+        let steps = (bx - ax).abs().max((by - ay).abs()).max(1);
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            let x = ax + ((bx - ax) as f64 * t).round() as i64;
+            let y = ay + ((by - ay) as f64 * t).round() as i64;
+            if let Some(cell) = self
+                .cells
+                .get_mut(y as usize)
+                .and_then(|r| r.get_mut(x as usize))
+            {
+                *cell = c(*cell);
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for _Canvas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in &self.cells {
+            writeln!(f, "{}", row.iter().collect::<String>().trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// characters are about twice as tall as they are wide, so x gets stretched
+fn _to_cell(p: _Point) -> (i64, i64) {
+    ((p.x * 2.0).round() as i64, p.y.round() as i64)
+}
+
+/// (side, index) of every letter in the solution, in order, following the same rules as
+/// LBPuzzle::validate_solution. Stops at the first letter that can't be found.
+pub(crate) fn _letter_positions<const S: usize, const L: usize>(
+    puzzle: &LBPuzzle<S, L>,
+    solution: &LBPuzzleSolution,
+) -> Vec<(usize, usize)> {
+    let mut letters: Vec<char> = solution
+        .first()
+        .map(|w| w.chars().collect())
+        .unwrap_or_default();
+    for word in solution.iter().skip(1) {
+        letters.extend(word.chars().skip(1));
+    }
+
+    let mut positions: Vec<(usize, usize)> = Vec::new();
+    for letter in letters {
+        let prev_side = positions.last().map(|&(side, _)| side);
+        let found = puzzle.sides().iter().enumerate().find_map(|(i, side)| {
+            if Some(i) == prev_side {
+                return None;
+            }
+            side.iter().position(|l| *l == letter).map(|j| (i, j))
+        });
+        match found {
+            Some(position) => positions.push(position),
+            None => break,
+        }
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Charset, Renderer};
+    use crate::{LBPuzzle, NYTBoxPuzzle};
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_render_box() {
+        let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+        let covered: BTreeSet<char> = "juvenile".chars().collect();
+        let rendered = Renderer::new(Charset::Ascii).render_box(&puzzle, &covered);
+        println!("{}", rendered);
+        assert_eq!(
+            rendered,
+            r"      E   r   b
+  +---------------+
+  |               |
+J |               | U
+  |               |
+a |               | L
+  |               |
+V |               | N
+  |               |
+  +---------------+
+      I   m   k
+"
+        );
+    }
+
+    #[test]
+    fn test_render_solution() {
+        let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+        let solution = vec!["bum".to_string(), "mane".to_string()];
+        let rendered = Renderer::default().render_solution(&puzzle, &solution);
+        println!("{}", rendered);
+        assert_eq!(
+            rendered,
+            r"      E   r   B
+  ┌───────────────┐
+  │   ∘∘      ·   │
+j │     ∘∘     ·· │ U
+  │       ∘∘   ·  │
+A │ ∘∘∘     ∘∘·   │ l
+  │   ∘∘∘∘∘∘∘·∘∘  │
+v │     ∘∘ · ∘∘∘∘ │ N
+  │       ∘       │
+  └───────────────┘
+      i   M   k
+"
+        );
+    }
+
+    #[test]
+    fn test_render_other_shapes() {
+        let triangle = LBPuzzle::<3, 2>::from_str(3, "ab cd ef").unwrap();
+        let rendered = Renderer::default().render_box(&triangle, &BTreeSet::new());
+        println!("{}", rendered);
+        assert_eq!(
+            rendered,
+            r"     a     b
+•───────────────•
+ ╲             ╱
+e ╲           ╱ c
+   ╲         ╱
+  f  ╲     ╱  d
+      ╲   ╱
+       ╲ ╱
+        •
+"
+        );
+
+        let pentagon = LBPuzzle::<5, 3>::from_str(3, "abc def ghi jkl mno").unwrap();
+        let solution = vec!["adgjm".to_string()];
+        let rendered = Renderer::new(Charset::Ascii).render_solution(&pentagon, &solution);
+        println!("{}", rendered);
+        assert_eq!(
+            rendered,
+            r"         A   b   c
+      +-------------+
+     /   .....       \
+  M  / .      ...... \  D
+ n  /  .           .  \  e
+    /  .           .  \
+o  /  .             .  \  f
+   /  .             .  \
+  +   ...............   +
+ J   \               /   G
+    k  \          /   h
+       l  \     /  i
+             +
+"
+        );
+    }
+}
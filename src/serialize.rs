@@ -0,0 +1,143 @@
+//! Serde support for LBPuzzle, behind the "serde" feature.
+//!
+//! A puzzle is written as its sides (one string each), its max number of words, and its shape:
+//!
+//! ```json
+//! {"sides": ["erb", "uln", "imk", "jav"], "max_words": 6, "shape": {"sides": 4, "letters_per_side": 3}}
+//! ```
+//!
+//! Reading one back checks it over first, so a malformed board can't be constructed this way:
+//! the shape (if given) has to match the type being read into, and the rest gets the same checks as
+//! LBPuzzle::from_str (see LBPuzzle::from_sides).
+
+use crate::LBPuzzle;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+struct _Shape {
+    sides: usize,
+    letters_per_side: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct _PuzzleRepr {
+    sides: Vec<String>,
+    max_words: usize,
+    // optional on the way in, since the type being read into already says what it should be
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    shape: Option<_Shape>,
+}
+
+impl<const S: usize, const L: usize> Serialize for LBPuzzle<S, L> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        _PuzzleRepr {
+            sides: self.sides().iter().map(|s| s.iter().collect()).collect(),
+            max_words: self.max_words(),
+            shape: Some(_Shape {
+                sides: S,
+                letters_per_side: L,
+            }),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, const S: usize, const L: usize> Deserialize<'de> for LBPuzzle<S, L> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = _PuzzleRepr::deserialize(deserializer)?;
+        if let Some(shape) = &repr.shape {
+            if (shape.sides, shape.letters_per_side) != (S, L) {
+                return Err(D::Error::custom(format!(
+                    "expected a {}x{} puzzle, got {}x{}",
+                    S, L, shape.sides, shape.letters_per_side
+                )));
+            }
+        }
+        LBPuzzle::from_sides(repr.max_words, &repr.sides).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::batch::BatchSummary;
+    use crate::{LBPuzzle, LBPuzzleSolution, NYTBoxPuzzle};
+
+    #[test]
+    fn test_puzzle_json() {
+        let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+        let json = serde_json::to_string(&puzzle).unwrap();
+        assert_eq!(
+            json,
+            r#"{"sides":["erb","uln","imk","jav"],"max_words":6,"shape":{"sides":4,"letters_per_side":3}}"#
+        );
+        let back: NYTBoxPuzzle = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.sides(), puzzle.sides());
+        assert_eq!(back.max_words(), puzzle.max_words());
+
+        // the shape's optional, and letters get lowercased like from_str
+        let back: NYTBoxPuzzle =
+            serde_json::from_str(r#"{"sides":["ERB","uln","imk","jav"],"max_words":6}"#).unwrap();
+        assert_eq!(back.sides(), puzzle.sides());
+
+        let malformed = [
+            // wrong shape for the type
+            r#"{"sides":["erb","uln","imk","jav"],"max_words":6,"shape":{"sides":3,"letters_per_side":4}}"#,
+            // too few sides, or letters on a side
+            r#"{"sides":["erb","uln","imk"],"max_words":6}"#,
+            r#"{"sides":["erb","uln","imk","ja"],"max_words":6}"#,
+            // the same letter twice, or a space
+            r#"{"sides":["erb","uln","imk","jae"],"max_words":6}"#,
+            r#"{"sides":["erb","uln","imk","j v"],"max_words":6}"#,
+            // no room for any words, or no max at all
+            r#"{"sides":["erb","uln","imk","jav"],"max_words":0}"#,
+            r#"{"sides":["erb","uln","imk","jav"]}"#,
+        ];
+        for json in malformed {
+            println!("TEST: {}", json);
+            assert!(serde_json::from_str::<NYTBoxPuzzle>(json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_puzzle_toml() {
+        let puzzle = LBPuzzle::<3, 2>::from_str(3, "ab cd ef").unwrap();
+        let text = toml::to_string(&puzzle).unwrap();
+        assert_eq!(
+            text,
+            "sides = [\"ab\", \"cd\", \"ef\"]\nmax_words = 3\n\n[shape]\nsides = 3\nletters_per_side = 2\n"
+        );
+        let back: LBPuzzle<3, 2> = toml::from_str(&text).unwrap();
+        assert_eq!(back.sides(), puzzle.sides());
+        assert!(toml::from_str::<LBPuzzle<3, 2>>(
+            "sides = [\"ab\", \"cd\", \"ee\"]\nmax_words = 3\n"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_reports() {
+        let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+        let solution: LBPuzzleSolution = vec!["juvenile".to_string(), "embark".to_string()];
+        let json = serde_json::to_string(&solution).unwrap();
+        assert_eq!(json, r#"["juvenile","embark"]"#);
+
+        let report = puzzle.check_solution(&vec!["juvenile".to_string()], None);
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            json,
+            r#"{"words":["juvenile"],"problems":["Bad solution: Not all letters were used."]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<crate::SolutionReport>(&json).unwrap(),
+            report
+        );
+
+        let summary = BatchSummary {
+            solved: 2,
+            ..Default::default()
+        };
+        let text = toml::to_string(&summary).unwrap();
+        assert_eq!(toml::from_str::<BatchSummary>(&text).unwrap(), summary);
+    }
+}
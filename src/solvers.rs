@@ -1,19 +1,71 @@
 //! Module containing strategies for solving puzzles.
 //!
-//! Each strategy is a trait with a single method `solve`, which takes a puzzle and returns a solution.
+//! Each strategy implements the `SolverStrategy` trait, whose `solve` method takes a puzzle and returns a solution.
+//! They can also finish a partial solution with `solve_from`, e.g. for a player who got stuck.
+//! Strategies can also be looked up by name with the `registry` module.
 //!
 //! The strategies are:
 //! - `AStar`: Uses A* search with a custom heuristic.
-//! - `BruteForce`: Uses a really stupid brute force approach to check all possible words.
+//! - `BruteForce`: Walks the puzzle letter by letter over a trie. Slow, but simple enough to serve as a
+//!   reference for the others on small puzzles.
 //! - `PreDict`: Uses a precomputed dictionary to speed up the search, but still isn't that smart about it.
+//! - `Iddfs`: Iterative-deepening DFS over the precomputed dictionary, with bitmask pruning. Optimal, and
+//!   uses very little memory.
+//!
+//! Any strategy, including ones outside this crate, can be checked against the `conformance` suite.
 
 pub mod a_star;
 pub mod brute_force;
+pub mod conformance;
+pub mod iddfs;
 pub mod pre_dict;
+pub mod registry;
 
-use crate::{LBPuzzle, LBPuzzleSolution};
+use crate::dictionary::smart_dict::SmartDictionary;
+use crate::{LBPuzzle, LBPuzzleSolution, Result};
 
 /// Strategy for solving a puzzle
 pub trait SolverStrategy<const L: usize, const S: usize> {
-    fn solve(&self, puzzle: &LBPuzzle<L, S>) -> Option<LBPuzzleSolution>;
+    /// solve the puzzle using the default dictionary
+    fn solve(&self, puzzle: &LBPuzzle<L, S>) -> Option<LBPuzzleSolution> {
+        let dict = SmartDictionary::new(puzzle);
+        self.solve_with_dict(puzzle, &dict)
+    }
+
+    /// solve the puzzle using only words from the given dictionary
+    fn solve_with_dict(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &SmartDictionary,
+    ) -> Option<LBPuzzleSolution> {
+        self.solve_remainder(puzzle, dict, &[])
+    }
+
+    /// Finish a puzzle that a player has already started, e.g. because they got stuck.
+    /// Validates the player's words, then returns the whole solution: their words, followed by as
+    /// few words as the solver can manage.
+    fn solve_from(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &SmartDictionary,
+        prefix: &LBPuzzleSolution,
+    ) -> Result<Option<LBPuzzleSolution>> {
+        puzzle.validate_partial_solution(prefix)?;
+        Ok(self
+            .solve_remainder(puzzle, dict, prefix)
+            .map(|rest| prefix.iter().cloned().chain(rest).collect()))
+    }
+
+    /// Solve the rest of the puzzle, starting from the last letter of "prefix" with everything it
+    /// covers already covered. Returns only the words after the prefix (so an empty solution means
+    /// the prefix already covers everything).
+    ///
+    /// The prefix must already be valid per LBPuzzle::validate_partial_solution, but its words
+    /// don't need to be in the dictionary. An empty prefix means solving from scratch.
+    fn solve_remainder(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &SmartDictionary,
+        prefix: &[String],
+    ) -> Option<LBPuzzleSolution>;
 }
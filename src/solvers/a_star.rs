@@ -1,6 +1,7 @@
-use log::{debug, info};
+use log::info;
 use pathfinding::prelude::astar;
-use std::collections::BTreeSet;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::hash::Hash;
 
 use super::SolverStrategy;
@@ -69,15 +70,124 @@ impl Vertex {
 ///
 /// Note 2: that at some point we could be smarter and prefer easier letters to hard ones (maybe use
 /// scrabble letter values?), but this is a good option to start with.
+///
+/// Note 3: h(v) above is the default, but it's pretty weak--it can't tell "1 more word" from "3 more
+/// words". See [Heuristic] for more informative alternatives, selectable with `with_heuristic`.
+///
+/// Note 4: lots of puzzles have several solutions with the same number of words. See [Objective]
+/// (selectable with `with_objective`) for ways to break those ties.
+///
+/// Note 5: when there's no solution at all (e.g. with a restricted dictionary), `solve_best_partial`
+/// returns the path that gets closest instead.
 pub struct AStarSolver<const L: usize, const S: usize> {
     /// value between 1 and (L*S)
     edge_weight: u32,
+    heuristic: Heuristic,
+    objective: Objective,
+}
+
+/// What the solver minimizes.
+///
+/// Every objective minimizes the number of words first. The others then break ties between
+/// solutions with the same number of words. We do that by giving each edge a secondary cost, and
+/// scaling the word cost & heuristic up by a factor larger than any path's total secondary cost:
+///
+/// |e| = (edge_weight * scale) + secondary(e)
+///
+/// So one extra word always outweighs any difference in secondary cost, and the heuristic (which
+/// ignores secondary costs) stays admissible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Objective {
+    /// fewest words, with ties broken arbitrarily
+    #[default]
+    WordCount,
+    /// fewest words, then fewest total letters
+    WordsThenLetters,
+    /// fewest words, then the lowest sum of word rarity (i.e. rank in the source dictionary)
+    WordsThenRarity,
+}
+
+/// The heuristics available to AStarSolver.
+///
+/// With an edge weight factor of 1.0, all of these are admissible, so they all give optimal solutions.
+/// The more informative ones just get there visiting fewer nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Heuristic {
+    /// h(v) = (L*S) - |coverage(v)|
+    #[default]
+    Coverage,
+    /// h(v) = |e| * ceil(uncovered(v) / max_new), where max_new is the most letters any one word covers.
+    ///
+    /// No word can cover more than max_new new letters, so this is a lower bound on the number of
+    /// words left. It's scaled by the actual edge weight, so it stays admissible for any weight factor.
+    MaxNewLetters,
+    /// Same as MaxNewLetters, but if that says 1 more word, we check whether any single word
+    /// starting at v.letter actually finishes the puzzle. If none does, we need at least 2.
+    Lookahead,
+}
+
+/// per-search precomputed values for the heuristics & edge costs, so we don't recompute them for every vertex.
+struct _SearchTable {
+    // every edge's word cost is multiplied by this, per Objective
+    scale: u64,
+    // the most words we may still use
+    max_words: usize,
+    // the most letters any single word covers
+    max_new: usize,
+    // coverage of every word, grouped by starting letter. the start vertex (None) gets all of them.
+    coverages: BTreeMap<Option<char>, Vec<BTreeSet<char>>>,
+}
+
+impl _SearchTable {
+    fn new(dict: &smart_dict::SmartDictionary, objective: Objective, max_words: usize) -> Self {
+        let mut coverages: BTreeMap<Option<char>, Vec<BTreeSet<char>>> = BTreeMap::new();
+        for (_, w) in dict.get_flat_indexed() {
+            let coverage_e: BTreeSet<char> = w.chars().collect();
+            coverages
+                .entry(w.chars().next())
+                .or_default()
+                .push(coverage_e.clone());
+            coverages.entry(None).or_default().push(coverage_e);
+        }
+        let max_new = coverages
+            .get(&None)
+            .and_then(|all| all.iter().map(|c| c.len()).max())
+            .unwrap_or(0);
+
+        // the total secondary cost of any path is at most max_words * (the max for a single word)
+        let max_secondary = dict
+            .get_flat_indexed()
+            .iter()
+            .map(|(idx, w)| _secondary_cost(objective, dict, *idx, w))
+            .max()
+            .unwrap_or(0);
+        let scale = (max_words as u64 * max_secondary) + 1;
+
+        Self {
+            scale,
+            max_words,
+            max_new,
+            coverages,
+        }
+    }
+
+    /// true if some single word starting at this vertex's letter covers everything left
+    fn can_finish_in_one(&self, v: &Vertex, all_letters: &BTreeSet<char>) -> bool {
+        let uncovered: BTreeSet<char> = all_letters.difference(&v.coverage).cloned().collect();
+        self.coverages
+            .get(&v.letter)
+            .is_some_and(|cs| cs.iter().any(|c| c.is_superset(&uncovered)))
+    }
 }
 
 impl<const L: usize, const S: usize> SolverStrategy<L, S> for AStarSolver<L, S> {
-    fn solve(&self, puzzle: &LBPuzzle<L, S>) -> Option<LBPuzzleSolution> {
-        let dict = smart_dict::SmartDictionary::new(&puzzle);
-        self._helper(&puzzle, &dict)
+    fn solve_remainder(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+        prefix: &[String],
+    ) -> Option<LBPuzzleSolution> {
+        self._helper_from(puzzle, dict, prefix)
     }
 }
 
@@ -86,31 +196,45 @@ impl<const L: usize, const S: usize> AStarSolver<L, S> {
     /// it will set edge weight to some integer value between 1 and (L*S)
     pub fn new(edge_weight_factor: f32) -> Self {
         Self {
-            edge_weight: (edge_weight_factor * (L * S) as f32).round() as u32,
+            edge_weight: ((edge_weight_factor * (L * S) as f32).round() as u32).max(1),
+            heuristic: Heuristic::default(),
+            objective: Objective::default(),
         }
     }
 
+    /// optimize for something more specific than just the word count
+    pub fn with_objective(mut self, objective: Objective) -> Self {
+        self.objective = objective;
+        self
+    }
+
+    /// use a different heuristic than the default
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
     /// returns all successor nodes, i.e. ending letters & coverages for all words with this starting letter
     fn successors(
         &self,
         v: &Vertex,
         dict: &smart_dict::SmartDictionary,
-        puzzle: &LBPuzzle<L, S>,
-    ) -> Option<Vec<(Vertex, u32)>> {
+        table: &_SearchTable,
+    ) -> Option<Vec<(Vertex, u64)>> {
         // BASE CASE: we've visited the max number of words
-        if v._words_path.clone().unwrap_or_default().len() == puzzle.max_words {
+        if v._words_path.clone().unwrap_or_default().len() == table.max_words {
             return None;
         }
         // gather all dictionary words that start with this letter
         let next_words = match v.letter {
-            Some(l_) => dict.get_indexed(l_).unwrap_or(Vec::new()),
+            Some(l_) => dict.get_indexed(l_).unwrap_or_default(),
             None => dict.get_flat_indexed().clone(),
         };
 
         // for each, construct the next vertex & assign an edge weight & return
         let successors = next_words
             .into_iter()
-            .map(|(idx, w)| -> (Vertex, u32) {
+            .map(|(idx, w)| -> (Vertex, u64) {
                 // coverage(v) = coverage(v') + coverage(e)
                 // i could do something clever here to save memory by caching identical coverages.
                 // we'll see if we need it.
@@ -123,15 +247,60 @@ impl<const L: usize, const S: usize> AStarSolver<L, S> {
                 words_path.push(idx);
 
                 let new_v = Vertex::new(w.chars().last(), coverage, Some(words_path));
-                (new_v, self.edge_weight)
+                (new_v, self.edge_cost(dict, idx, &w, table))
             })
             .collect();
         Some(successors)
     }
 
-    /// h(v) = (L*S) - coverage(v)
-    fn heuristic(&self, v: &Vertex, _puzzle: &LBPuzzle<L, S>) -> u32 {
-        ((L * S) - v.coverage.len()) as u32
+    /// |e| = (edge_weight * scale) + secondary(e), per Objective
+    fn edge_cost(
+        &self,
+        dict: &smart_dict::SmartDictionary,
+        idx: usize,
+        w: &str,
+        table: &_SearchTable,
+    ) -> u64 {
+        (self.edge_weight as u64 * table.scale) + _secondary_cost(self.objective, dict, idx, w)
+    }
+
+    /// estimate the cost to finish from v, per self.heuristic
+    fn heuristic(&self, v: &Vertex, table: &_SearchTable, all_letters: &BTreeSet<char>) -> u64 {
+        self.heuristic_words(v, table, all_letters) as u64 * table.scale
+    }
+
+    /// the heuristic, in units of edge weight (i.e. ignoring the objective's scaling)
+    fn heuristic_words(
+        &self,
+        v: &Vertex,
+        table: &_SearchTable,
+        all_letters: &BTreeSet<char>,
+    ) -> u32 {
+        let uncovered = (L * S) - v.coverage.len();
+        match self.heuristic {
+            Heuristic::Coverage => uncovered as u32,
+            Heuristic::MaxNewLetters | Heuristic::Lookahead => {
+                if uncovered == 0 {
+                    return 0;
+                }
+                if table.max_new == 0 {
+                    // no words at all, so we'll never finish. any value is admissible.
+                    return self.edge_weight;
+                }
+                let mut n_words = uncovered.div_ceil(table.max_new) as u32;
+                if self.heuristic == Heuristic::Lookahead
+                    && n_words == 1
+                    && !table.can_finish_in_one(v, all_letters)
+                {
+                    n_words = 2;
+                }
+                n_words * self.edge_weight
+            }
+        }
+    }
+
+    fn is_goal(&self, v: &Vertex) -> bool {
+        v.coverage.len() == L * S
     }
 
     /// Helper function for A* search.
@@ -141,7 +310,87 @@ impl<const L: usize, const S: usize> AStarSolver<L, S> {
         puzzle: &LBPuzzle<L, S>,
         dict: &smart_dict::SmartDictionary,
     ) -> Option<LBPuzzleSolution> {
-        let start = Vertex::new_start();
+        self._helper_from(puzzle, dict, &[])
+    }
+
+    /// Best-partial mode: the path covering the most letters within max_words words, with ties
+    /// broken by fewer words, then by the objective. So if a path covers everything, it's the same
+    /// solution _helper would find. Returns None only if no words can be played at all.
+    ///
+    /// Without a goal to aim for, A* would have to visit every path. Instead, this walks the same
+    /// graph breadth-first, a word at a time. Paths with the same number of words which reach the
+    /// same letter & coverage can finish the same ways, so only the cheapest is kept, and anything
+    /// already reached in fewer words is skipped.
+    pub fn solve_best_partial(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+    ) -> Option<LBPuzzleSolution> {
+        let table = _SearchTable::new(dict, self.objective, puzzle.max_words);
+        let mut seen: HashSet<(Option<char>, BTreeSet<char>)> = HashSet::new();
+        let mut best = Vertex::new_start();
+        let mut layer = vec![(Vertex::new_start(), 0)];
+
+        while !layer.is_empty() {
+            // the cheapest path to each (letter, coverage) with one more word
+            let mut next_layer = BTreeMap::new();
+            for (v, cost) in &layer {
+                for (next, edge_cost) in self.successors(v, dict, &table).unwrap_or_default() {
+                    let key = (next.letter, next.coverage.clone());
+                    let cost = cost + edge_cost;
+                    if seen.contains(&key) || next_layer.get(&key).is_some_and(|(_, c)| *c <= cost)
+                    {
+                        continue;
+                    }
+                    next_layer.insert(key, (next, cost));
+                }
+            }
+            seen.extend(next_layer.keys().cloned());
+            layer = next_layer.into_values().collect();
+
+            let Some((layer_best, _)) = layer
+                .iter()
+                .min_by_key(|(v, cost)| (Reverse(v.coverage.len()), *cost))
+            else {
+                break;
+            };
+            if layer_best.coverage.len() > best.coverage.len() {
+                best = layer_best.clone();
+            }
+            if self.is_goal(&best) {
+                // the first layer with a goal has the fewest words, & it's the cheapest of those
+                break;
+            }
+        }
+
+        let word_path = best
+            ._words_path?
+            .iter()
+            .map(|idx| dict.get_word_by_idx(*idx).unwrap().as_ref().clone())
+            .collect();
+        info!("Best partial path: {:?}", word_path);
+        Some(word_path)
+    }
+
+    /// Same as _helper, but the start vertex is wherever "prefix" leaves off, rather than
+    /// Vertex::new_start(). See SolverStrategy::solve_remainder.
+    fn _helper_from(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+        prefix: &[String],
+    ) -> Option<LBPuzzleSolution> {
+        let max_words = puzzle.max_words.checked_sub(prefix.len())?;
+        let start = match prefix.last() {
+            Some(last) => Vertex::new(
+                last.chars().last(),
+                prefix.iter().flat_map(|w| w.chars()).collect(),
+                None,
+            ),
+            None => Vertex::new_start(),
+        };
+        let table = _SearchTable::new(dict, self.objective, max_words);
+        let all_letters: BTreeSet<char> = puzzle.all_letters().chars().collect();
         let mut n_nodes_visited: u64 = 0;
         let mut n_edges_traversed: u64 = 0;
 
@@ -155,10 +404,10 @@ impl<const L: usize, const S: usize> AStarSolver<L, S> {
                 //     let cost = v._words_path.clone().unwrap_or_default().len();
                 //     debug!("Nodes visited: {}...g(v)={}", n_nodes_visited, cost);
                 // }
-                self.successors(&v, &dict, puzzle).unwrap_or(Vec::new())
+                self.successors(v, dict, &table).unwrap_or_default()
             },
             |v| {
-                let heur = self.heuristic(&v, &puzzle);
+                let heur = self.heuristic(v, &table, &all_letters);
                 n_edges_traversed += 1;
                 // #[cfg(debug_assertions)]
                 // if (n_edges_traversed % 100000) == 0 {
@@ -167,13 +416,13 @@ impl<const L: usize, const S: usize> AStarSolver<L, S> {
                 // }
                 heur
             },
-            |v| self.heuristic(&v, &puzzle) == 0,
+            |v| self.is_goal(v),
         );
 
         // parse the solution
         let path = match result {
             Some((path, cost)) => {
-                if (path.len() - 1) != (cost / ((L * S) as u32)) as usize {
+                if (path.len() - 1) != (cost / (self.edge_weight as u64 * table.scale)) as usize {
                     // path is 1 node longer than cost (aka n_words) because of the start node.
                     panic!(
                         "word len ({cost}) != path len ({}) -- the algo isn't working right",
@@ -190,12 +439,9 @@ impl<const L: usize, const S: usize> AStarSolver<L, S> {
             n_nodes_visited, n_edges_traversed
         );
 
-        // convert from index path to words
-        let idx_path = path?
-            .last()?
-            ._words_path
-            .clone()
-            .expect("This should have length");
+        // convert from index path to words. the start vertex has no path, but it can still be
+        // the goal if the prefix already covers everything.
+        let idx_path = path?.last()?._words_path.clone().unwrap_or_default();
         let word_path: Vec<String> = idx_path
             .iter()
             .map(|idx| dict.get_word_by_idx(*idx).unwrap().as_ref().clone())
@@ -205,3 +451,78 @@ impl<const L: usize, const S: usize> AStarSolver<L, S> {
         Some(word_path)
     }
 }
+
+/// secondary(e) for a single word, per Objective
+fn _secondary_cost(
+    objective: Objective,
+    dict: &smart_dict::SmartDictionary,
+    idx: usize,
+    w: &str,
+) -> u64 {
+    match objective {
+        Objective::WordCount => 0,
+        Objective::WordsThenLetters => w.chars().count() as u64,
+        Objective::WordsThenRarity => dict.get_rank_by_idx(idx) as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AStarSolver, Objective};
+    use crate::dictionary::smart_dict::SmartDictionary;
+    use crate::NYTBoxPuzzle;
+
+    #[test]
+    fn test_solve_best_partial() {
+        // no "i" words, so the best we can do is bum-murk-kale (9 letters)
+        let puzzle = NYTBoxPuzzle::from_str(3, "erb uln imk jav").unwrap();
+        let dict =
+            SmartDictionary::new_from_file(&puzzle, "bum\nmane\nmurk\njuv\nkale\n".as_bytes());
+        let solver = AStarSolver::new(1.0);
+        assert_eq!(
+            solver.solve_best_partial(&puzzle, &dict).unwrap(),
+            vec!["bum", "murk", "kale"]
+        );
+
+        // the same coverage in fewer words wins
+        let dict =
+            SmartDictionary::new_from_file(&puzzle, "bum\nmurk\nkale\nbumurkale\n".as_bytes());
+        assert_eq!(
+            solver.solve_best_partial(&puzzle, &dict).unwrap(),
+            vec!["bumurkale"]
+        );
+
+        // nothing to play
+        let dict = SmartDictionary::new_from_file(&puzzle, "".as_bytes());
+        assert_eq!(solver.solve_best_partial(&puzzle, &dict), None);
+    }
+
+    #[test]
+    fn test_solve_best_partial_full_solution() {
+        // juvenile + emubark or juvenile + embark; with an objective, it should agree with A*
+        let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+        let dict = SmartDictionary::new_from_file(
+            &puzzle,
+            "juvenile\nemubark\nembark\nbum\nmane\n".as_bytes(),
+        );
+        for (objective, expected) in [
+            (Objective::WordsThenLetters, vec!["juvenile", "embark"]),
+            (Objective::WordsThenRarity, vec!["juvenile", "emubark"]),
+        ] {
+            let solver = AStarSolver::new(1.0).with_objective(objective);
+            let partial = solver.solve_best_partial(&puzzle, &dict).unwrap();
+            assert_eq!(partial, expected);
+            assert_eq!(Some(partial), solver._helper(&puzzle, &dict));
+        }
+
+        // too few words allowed to finish
+        let puzzle = NYTBoxPuzzle::from_str(1, "erb uln imk jav").unwrap();
+        let dict = SmartDictionary::new_from_file(&puzzle, "juvenile\nembark\n".as_bytes());
+        assert_eq!(
+            AStarSolver::new(1.0)
+                .solve_best_partial(&puzzle, &dict)
+                .unwrap(),
+            vec!["juvenile"]
+        );
+    }
+}
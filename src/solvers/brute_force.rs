@@ -1,137 +1,200 @@
-use crate::dictionary::load_trie_dictionary;
+use crate::dictionary::smart_dict;
 use crate::{LBPuzzle, LBPuzzleSolution};
-use std::collections::{HashSet, VecDeque};
+use log::info;
+use trie_rs::inc_search::{Answer, IncSearch};
 use trie_rs::Trie;
 
 use super::SolverStrategy;
 
-#[derive(Debug)]
-struct _Solution {
-    pub words: LBPuzzleSolution,
-    // the index of the last-visited letter
-    pub last_idx: usize,
-    // all the letters we've visited before. always length L*S
-    pub visited_letters: Vec<bool>,
+/// everything the letter-by-letter walk needs to carry around
+struct _Walk<'a, const L: usize, const S: usize> {
+    puzzle: &'a LBPuzzle<L, S>,
+    dict: &'a Trie<u8>,
+    letters: Vec<char>,
+    // the max number of words we're allowed to use on this pass
+    word_limit: usize,
+    // how many times we've visited each letter, so we can back out of a step cheaply
+    visits: Vec<u32>,
+    // finished words, followed by the word currently being spelled
+    words: LBPuzzleSolution,
+    n_steps: u64,
 }
 
-impl _Solution {
-    /// generate a copy of this solution where we've ended the current word and have
-    /// started the next one (though it still needs a next letter)
-    pub fn end_word(&self) -> Self {
-        // end this word by starting another empty one
-        let mut words = self.words.clone();
-        words.push(String::from(words.last().unwrap().chars().last().unwrap()));
-
-        _Solution {
-            words,
-            last_idx: self.last_idx,
-            visited_letters: self.visited_letters.clone(),
-        }
-    }
-
-    pub fn clone(&self) -> Self {
-        _Solution {
-            words: self.words.clone(),
-            last_idx: self.last_idx,
-            visited_letters: self.visited_letters.clone(),
-        }
-    }
-}
-
-/// idiotic solver that just goes through every combo in DFS til it finds something that works.
+/// dumb-but-correct solver that walks the puzzle one letter at a time.
+///
+/// No dynamic programming, no clever optimizations, no nothing--it's meant to be obviously
+/// correct so that it can serve as a reference oracle for the smarter solvers on small puzzles.
+///
+/// At every step it either:
+/// - hops to a letter on another side, if the current word + that letter is still a prefix of
+///   some dictionary word (which is the only pruning it does), or
+/// - ends the current word if it's a real word, and starts the next one on its last letter.
 ///
-/// going about this the easy stupid way first to get a baseline.
-/// no dynamic programming, no clever optimizations, no nothing. Just a ton of wasted memory on string allocs.
-/// it doesn't try to find the best solution; it just returns the first valid solution
-/// it can find by doing recursive breadth-first search on the entire tree of possibilities.
+/// It does this as depth-first search, first allowing 1 word, then 2, ... up to max_words, so
+/// the first solution it finds uses as few words as possible.
 pub struct BruteForceSolver<const L: usize, const S: usize> {}
 
 impl<const L: usize, const S: usize> SolverStrategy<L, S> for BruteForceSolver<L, S> {
-    fn solve(&self, puzzle: &LBPuzzle<L, S>) -> Option<LBPuzzleSolution> {
-        let (dict, _) = load_trie_dictionary();
-
-        // may need to use linked list here instead due to allocating a huge block of contiguous mem but we'll see
-        let mut solution_queue: VecDeque<_Solution> = VecDeque::new();
-
-        println!("Initializing solutions...");
+    fn solve_remainder(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+        prefix: &[String],
+    ) -> Option<LBPuzzleSolution> {
+        self._helper_from(puzzle, dict, prefix)
+    }
+}
 
-        // initialize our solution queue with solutions starting with each letter
-        for (i, letter) in puzzle.all_letters().chars().enumerate() {
-            let mut words = LBPuzzleSolution::new();
-            words.push(letter.to_string());
-            let visited_letters = vec![false; L * S];
+impl<const L: usize, const S: usize> BruteForceSolver<L, S> {
+    /// Helper function for the search.
+    /// broken out separately for benchmarking purposes.
+    pub fn _helper(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+    ) -> Option<LBPuzzleSolution> {
+        self._helper_from(puzzle, dict, &[])
+    }
 
-            let soln = _Solution {
-                words,
-                last_idx: i,
-                visited_letters,
-            };
-            solution_queue.push_back(soln);
+    /// Same as _helper, but walks on from wherever "prefix" leaves off.
+    /// See SolverStrategy::solve_remainder.
+    fn _helper_from(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+        prefix: &[String],
+    ) -> Option<LBPuzzleSolution> {
+        let trie = dict.to_trie();
+        let mut walk = _Walk {
+            puzzle,
+            dict: &trie,
+            letters: puzzle.all_letters().chars().collect(),
+            word_limit: 0,
+            visits: vec![0; L * S],
+            words: prefix.to_vec(),
+            n_steps: 0,
+        };
+        // the prefix has already visited all of its letters
+        for c in prefix.iter().flat_map(|w| w.chars()) {
+            if let Some(idx) = walk.letters.iter().position(|&l| l == c) {
+                walk.visits[idx] += 1;
+            }
+        }
+        let last_idx = prefix
+            .last()
+            .and_then(|w| w.chars().last())
+            .and_then(|c| walk.letters.iter().position(|&l| l == c));
+        if last_idx.is_some() && walk.visits.iter().all(|&v| v > 0) {
+            return Some(LBPuzzleSolution::new());
         }
 
-        // now DFS over all possible options
-        while let Some(mut soln) = solution_queue.pop_front() {
-            // indicate that we've now visited this letter
-            // (doing it here so I don't have to write it every time I push to the queue)
-            soln.visited_letters[soln.last_idx] = true;
-            println!("Visiting solution: {:?}...", soln.words);
-
-            // cases
-            let curr_word = soln.words.last().expect("There should always be a word.");
-            // if our current letters make a word -- note that words must be 3 letters or greater
-            if curr_word.len() >= 3 && dict.exact_match(curr_word) {
-                // if we have a working solution, return it!
-                if soln.visited_letters.iter().all(|_l| *_l) {
-                    println!("Solution found! {soln:#?}");
-                    return Some(soln.words);
-                }
-
-                // otherwise, add this situation to the queue: the word ends here, and we start a new one.
-                // we need to do this for every valid letter
-                _add_all_valid_letters(&mut solution_queue, &dict, &puzzle, &soln.end_word());
+        for word_limit in (prefix.len() + 1)..=puzzle.max_words() {
+            walk.word_limit = word_limit;
+            let found = match last_idx {
+                Some(idx) => walk.start_word(idx),
+                None => (0..walk.letters.len()).any(|idx| {
+                    walk.words = vec![walk.letters[idx].to_string()];
+                    let mut search = trie.inc_search();
+                    match _query(&mut search, walk.letters[idx]) {
+                        Some(answer) => walk.step(idx, search, answer),
+                        None => false,
+                    }
+                }),
+            };
+            if found {
+                info!(
+                    "Solution found with {} words after {} steps: {:?}",
+                    word_limit, walk.n_steps, walk.words
+                );
+                return Some(walk.words.split_off(prefix.len()));
             }
-            // either way, if we have the ability to continue this word, let's try that too.
-            _add_all_valid_letters(&mut solution_queue, &dict, &puzzle, &soln);
         }
 
+        info!("No solution found after {} steps", walk.n_steps);
         None
     }
 }
 
-/// adds all letters that have possible future solutions to the queue
-fn _add_all_valid_letters<const L: usize, const S: usize>(
-    solution_queue: &mut VecDeque<_Solution>,
-    dict: &Trie<u8>,
-    puzzle: &LBPuzzle<L, S>,
-    soln_stub: &_Solution,
-) {
-    // yes i know this is inefficient, i said i was doing this the quick & dumb way to benchmark
-    let curr_word = soln_stub
-        .words
-        .last()
-        .expect("There should always be a last word.");
-    let mut letters = HashSet::<char>::new();
-    // todo how do i correctly type hint the iterator and use that directly rather than collecting?
-    let results: Vec<String> = dict.postfix_search(curr_word).collect();
-    for postfix in results {
-        letters.insert(
-            postfix
-                .chars()
-                .next()
-                .expect("There should always be a letter."),
-        );
+impl<'a, const L: usize, const S: usize> _Walk<'a, L, S> {
+    /// visit the letter at index "idx", which has already been appended to the current word.
+    /// "search" is the trie position of the current word, and "answer" is what it found there.
+    /// returns true if a solution was found, in which case it's left in self.words.
+    fn step(&mut self, idx: usize, search: IncSearch<'a, u8, ()>, answer: Answer) -> bool {
+        self.n_steps += 1;
+        self.visits[idx] += 1;
+        if (answer.is_match() && self.try_end_word(idx))
+            || (answer.is_prefix() && self.try_next_letters(idx, &search))
+        {
+            return true;
+        }
+        self.visits[idx] -= 1;
+        false
+    }
+
+    /// the current word is a real word, so try ending it here. Then either we're done, or we
+    /// start the next word on this same letter.
+    fn try_end_word(&mut self, idx: usize) -> bool {
+        let curr_word = self.words.last().expect("There should always be a word.");
+        // note that words must be 3 letters or greater
+        if curr_word.chars().count() < 3 {
+            return false;
+        }
+        if self.visits.iter().all(|&v| v > 0) {
+            return true;
+        }
+        if self.words.len() == self.word_limit {
+            return false;
+        }
+        self.start_word(idx)
+    }
+
+    /// start a new word on the letter at index "idx", which has already been visited.
+    fn start_word(&mut self, idx: usize) -> bool {
+        let mut search = self.dict.inc_search();
+        if _query(&mut search, self.letters[idx]).is_none() {
+            // no words start with this letter
+            return false;
+        }
+        self.words.push(self.letters[idx].to_string());
+        if self.try_next_letters(idx, &search) {
+            return true;
+        }
+        self.words.pop();
+        false
     }
 
-    // intersect our valid word letters with our available puzzle letters
-    let puzzle_valid_letters = &puzzle.valid_letters(soln_stub.last_idx as i32);
-    for letter in puzzle_valid_letters {
-        let mut next_word = curr_word.clone();
-        next_word.push(*letter);
-        if dict.exact_match(&next_word) {
-            let mut new_soln = soln_stub.clone();
-            new_soln.words.pop();
-            new_soln.words.push(next_word);
-            solution_queue.push_back(new_soln);
+    /// try continuing the current word with every letter on the other sides,
+    /// as long as the result is still (the start of) a dictionary word.
+    fn try_next_letters(&mut self, idx: usize, search: &IncSearch<'a, u8, ()>) -> bool {
+        for next_idx in 0..self.letters.len() {
+            if self.puzzle.idx_to_side(next_idx as i32) == self.puzzle.idx_to_side(idx as i32) {
+                continue;
+            }
+            let mut next_search = search.clone();
+            let answer = match _query(&mut next_search, self.letters[next_idx]) {
+                Some(answer) => answer,
+                None => continue,
+            };
+
+            let curr_word = self
+                .words
+                .last_mut()
+                .expect("There should always be a word.");
+            curr_word.push(self.letters[next_idx]);
+            if self.step(next_idx, next_search, answer) {
+                return true;
+            }
+            self.words
+                .last_mut()
+                .expect("There should always be a word.")
+                .pop();
         }
+        false
     }
 }
+
+/// advance the search by a single letter
+fn _query(search: &mut IncSearch<'_, u8, ()>, letter: char) -> Option<Answer> {
+    let mut buf = [0; 4];
+    search.query_until(letter.encode_utf8(&mut buf)).ok()
+}
@@ -0,0 +1,349 @@
+//! A conformance suite for any SolverStrategy, including ones built outside this crate.
+//!
+//! Each case is a small puzzle with a tiny dictionary of its own, so the suite is fast & doesn't
+//! depend on the bundled dictionaries. There are cases for NYT-shaped boards ([CASES]) and for
+//! triangles with 2 letters a side ([TRIANGLE_CASES]); see [cases]. Between them, the cases cover:
+//! - an ordinary solvable puzzle, and one that can also be finished from a partial solution
+//! - an unsolvable puzzle
+//! - a puzzle a single word can solve
+//! - max_words of 1 (too few) and 0
+//! - degenerate dictionaries: empty, all junk, and full of duplicates
+//!
+//! Every solution a solver returns must be valid, use only dictionary words, and fit in max_words.
+//! Solvers which claim to be optimal must also use the fewest words possible.
+//!
+//! ```
+//! use letterboxed_solver::solvers::{conformance, iddfs::IddfsSolver};
+//!
+//! conformance::assert_conforms::<4, 3>(&IddfsSolver {}, true);
+//! conformance::assert_conforms::<3, 2>(&IddfsSolver {}, true);
+//! ```
+
+use super::SolverStrategy;
+use crate::dictionary::smart_dict::SmartDictionary;
+use crate::LBPuzzle;
+use std::fmt;
+
+/// a single puzzle for a solver to solve
+#[derive(Debug, Clone, Copy)]
+pub struct ConformanceCase {
+    pub name: &'static str,
+    pub sides: &'static str,
+    pub max_words: usize,
+    /// the dictionary, newline-separated
+    pub words: &'static str,
+    /// words already played, to finish the puzzle from (see SolverStrategy::solve_from)
+    pub prefix: &'static [&'static str],
+    /// the fewest words a full solution can have (counting the prefix), or None if unsolvable
+    pub optimal_words: Option<usize>,
+}
+
+/// every conformance case for NYT-shaped boards, i.e. 4 sides of 3 letters
+pub const CASES: [ConformanceCase; 10] = [
+    ConformanceCase {
+        name: "solvable",
+        sides: "erb uln imk jav",
+        max_words: 6,
+        words: "bum\nmane\njuvenile\nembark\nmurk\nkale\n",
+        prefix: &[],
+        optimal_words: Some(2),
+    },
+    ConformanceCase {
+        name: "solvable from a partial solution",
+        sides: "erb uln imk jav",
+        max_words: 6,
+        words: "bum\nmane\njuvenile\nembark\nmurk\nkale\n",
+        prefix: &["juvenile"],
+        optimal_words: Some(2),
+    },
+    ConformanceCase {
+        name: "finishing a worse partial solution",
+        sides: "erb uln imk jav",
+        max_words: 6,
+        words: "bum\nmane\njuvenile\nembark\nmurk\nkale\nkanji\ninviable\n",
+        prefix: &["murk"],
+        optimal_words: Some(3),
+    },
+    ConformanceCase {
+        name: "unsolvable",
+        sides: "erb uln imk jav",
+        max_words: 6,
+        words: "bum\nmane\nmurk\n",
+        prefix: &[],
+        optimal_words: None,
+    },
+    ConformanceCase {
+        name: "single word",
+        sides: "erb uln imk jav",
+        max_words: 3,
+        words: "juvenile\nembark\njuvenilembark\n",
+        prefix: &[],
+        optimal_words: Some(1),
+    },
+    ConformanceCase {
+        name: "max_words=1",
+        sides: "erb uln imk jav",
+        max_words: 1,
+        words: "juvenile\nembark\n",
+        prefix: &[],
+        optimal_words: None,
+    },
+    ConformanceCase {
+        name: "max_words=0",
+        sides: "erb uln imk jav",
+        max_words: 0,
+        words: "juvenile\nembark\njuvenilembark\n",
+        prefix: &[],
+        optimal_words: None,
+    },
+    ConformanceCase {
+        name: "empty dictionary",
+        sides: "erb uln imk jav",
+        max_words: 6,
+        words: "",
+        prefix: &[],
+        optimal_words: None,
+    },
+    ConformanceCase {
+        name: "junk dictionary",
+        // comments, blank lines, short words, off-board letters, and same-side hops
+        sides: "erb uln imk jav",
+        max_words: 6,
+        words: "# juvenile\n\nju\nbe\nzebra\njuvenilez\nberry\nlull\n",
+        prefix: &[],
+        optimal_words: None,
+    },
+    ConformanceCase {
+        name: "duplicate words",
+        sides: "erb uln imk jav",
+        max_words: 6,
+        words: "juvenile\njuvenile\nembark\n  embark  \njuvenile\n",
+        prefix: &[],
+        optimal_words: Some(2),
+    },
+];
+
+/// conformance cases for triangles with 2 letters a side. fewer of them, since most of what the
+/// NYT cases check doesn't depend on the board's shape.
+pub const TRIANGLE_CASES: [ConformanceCase; 5] = [
+    ConformanceCase {
+        name: "solvable",
+        sides: "nb ue mr",
+        max_words: 3,
+        words: "member\nrun\nmen\n",
+        prefix: &[],
+        optimal_words: Some(2),
+    },
+    ConformanceCase {
+        name: "solvable from a partial solution",
+        sides: "nb ue mr",
+        max_words: 3,
+        words: "member\nrun\nmen\n",
+        prefix: &["member"],
+        optimal_words: Some(2),
+    },
+    ConformanceCase {
+        name: "unsolvable",
+        sides: "nb ue mr",
+        max_words: 3,
+        words: "member\nmen\n",
+        prefix: &[],
+        optimal_words: None,
+    },
+    ConformanceCase {
+        name: "single word",
+        sides: "nb ue mr",
+        max_words: 3,
+        words: "number\nmember\nrun\n",
+        prefix: &[],
+        optimal_words: Some(1),
+    },
+    ConformanceCase {
+        name: "max_words=1",
+        sides: "nb ue mr",
+        max_words: 1,
+        words: "member\nrun\n",
+        prefix: &[],
+        optimal_words: None,
+    },
+];
+
+/// the conformance cases for S sides of L letters (empty if there aren't any for that shape)
+pub fn cases<const S: usize, const L: usize>() -> &'static [ConformanceCase] {
+    match (S, L) {
+        (4, 3) => &CASES,
+        (3, 2) => &TRIANGLE_CASES,
+        _ => &[],
+    }
+}
+
+/// a case a solver got wrong, and how
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConformanceFailure {
+    pub case: &'static str,
+    pub problem: String,
+}
+
+impl fmt::Display for ConformanceFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.case, self.problem)
+    }
+}
+
+/// Run a single case. "optimal" says whether the solver claims to find the fewest words possible.
+pub fn check_case<const S: usize, const L: usize>(
+    solver: &dyn SolverStrategy<S, L>,
+    case: &ConformanceCase,
+    optimal: bool,
+) -> Result<(), ConformanceFailure> {
+    let fail = |problem: String| {
+        Err(ConformanceFailure {
+            case: case.name,
+            problem,
+        })
+    };
+    // from_str won't take max_words = 0, but LBPuzzle::new will, so solvers still have to cope
+    let puzzle = match LBPuzzle::<S, L>::from_str(case.max_words.max(1), case.sides) {
+        Ok(puzzle) => LBPuzzle::new(case.max_words, puzzle.sides()),
+        Err(e) => return fail(format!("bad case: {}", e)),
+    };
+    let dict = SmartDictionary::new_from_file(&puzzle, case.words.as_bytes());
+    let prefix: Vec<String> = case.prefix.iter().map(|w| w.to_string()).collect();
+
+    let solution = match solver.solve_from(&puzzle, &dict, &prefix) {
+        Ok(solution) => solution,
+        Err(e) => return fail(format!("rejected the prefix {:?}: {}", prefix, e)),
+    };
+    let (solution, optimal_words) = match (solution, case.optimal_words) {
+        (None, None) => return Ok(()),
+        (None, Some(n)) => {
+            return fail(format!("found nothing, but there's a {}-word solution", n))
+        }
+        (Some(solution), None) => {
+            return fail(format!("found {:?}, but there's no solution", solution))
+        }
+        (Some(solution), Some(n)) => (solution, n),
+    };
+
+    if let Err(e) = puzzle.validate_solution(&solution) {
+        return fail(format!("found an invalid solution {:?}: {}", solution, e));
+    }
+    if !solution.starts_with(&prefix) {
+        return fail(format!("{:?} doesn't start with {:?}", solution, prefix));
+    }
+    if let Some(word) = solution[prefix.len()..].iter().find(|w| !dict.contains(w)) {
+        return fail(format!(
+            "{:?} uses {}, which isn't in the dictionary",
+            solution, word
+        ));
+    }
+    if solution.len() > case.max_words {
+        return fail(format!(
+            "{:?} has more than {} words",
+            solution, case.max_words
+        ));
+    }
+    if optimal && solution.len() != optimal_words {
+        return fail(format!(
+            "{:?} isn't optimal, it should have {} words",
+            solution, optimal_words
+        ));
+    }
+    Ok(())
+}
+
+/// Run every case for this shape of board, returning the ones the solver got wrong.
+/// A shape without any cases is a failure too, rather than a silent pass.
+pub fn check_solver<const S: usize, const L: usize>(
+    solver: &dyn SolverStrategy<S, L>,
+    optimal: bool,
+) -> Vec<ConformanceFailure> {
+    let cases = cases::<S, L>();
+    if cases.is_empty() {
+        return vec![ConformanceFailure {
+            case: "(none)",
+            problem: format!("there are no conformance cases for {}x{} boards", S, L),
+        }];
+    }
+    cases
+        .iter()
+        .filter_map(|case| check_case(solver, case, optimal).err())
+        .collect()
+}
+
+/// Run every case, and panic with everything the solver got wrong. Handy in tests.
+pub fn assert_conforms<const S: usize, const L: usize>(
+    solver: &dyn SolverStrategy<S, L>,
+    optimal: bool,
+) {
+    let failures = check_solver(solver, optimal);
+    if !failures.is_empty() {
+        let failures: Vec<String> = failures.iter().map(|f| format!("  - {}", f)).collect();
+        panic!("solver failed conformance:\n{}", failures.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_solver, CASES, TRIANGLE_CASES};
+    use crate::dictionary::smart_dict::SmartDictionary;
+    use crate::solvers::SolverStrategy;
+    use crate::{LBPuzzle, LBPuzzleSolution};
+
+    /// always gives up
+    struct Quitter;
+    impl<const S: usize, const L: usize> SolverStrategy<S, L> for Quitter {
+        fn solve_remainder(
+            &self,
+            _: &LBPuzzle<S, L>,
+            _: &SmartDictionary,
+            _: &[String],
+        ) -> Option<LBPuzzleSolution> {
+            None
+        }
+    }
+
+    /// always gives the same answer, whether or not it's right
+    struct Parrot;
+    impl SolverStrategy<4, 3> for Parrot {
+        fn solve_remainder(
+            &self,
+            _: &LBPuzzle<4, 3>,
+            _: &SmartDictionary,
+            prefix: &[String],
+        ) -> Option<LBPuzzleSolution> {
+            let answer = ["juvenile".to_string(), "embark".to_string()];
+            Some(answer[prefix.len().min(2)..].to_vec())
+        }
+    }
+
+    #[test]
+    fn test_conformance_catches_bad_solvers() {
+        // the quitter only gets the unsolvable cases right
+        let n_unsolvable = CASES.iter().filter(|c| c.optimal_words.is_none()).count();
+        assert_eq!(
+            check_solver::<4, 3>(&Quitter, false).len(),
+            CASES.len() - n_unsolvable
+        );
+        let n_unsolvable = TRIANGLE_CASES
+            .iter()
+            .filter(|c| c.optimal_words.is_none())
+            .count();
+        assert_eq!(
+            check_solver::<3, 2>(&Quitter, false).len(),
+            TRIANGLE_CASES.len() - n_unsolvable
+        );
+        // no cases at all is a failure
+        let failures = check_solver::<2, 2>(&Quitter, false);
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].problem.contains("no conformance cases"));
+
+        let failures = check_solver(&Parrot, true);
+        let failed: Vec<&str> = failures.iter().map(|f| f.case).collect();
+        assert!(failed.contains(&"unsolvable"));
+        assert!(failed.contains(&"single word"));
+        assert!(failed.contains(&"max_words=1"));
+        assert!(!failed.contains(&"solvable"));
+        assert!(!failed.contains(&"duplicate words"));
+    }
+}
@@ -0,0 +1,440 @@
+//! Iterative-deepening depth-first solver over the precomputed dictionary.
+//!
+//! pre_dict runs out of memory because it clones the whole word list at every step and
+//! never really bounds its depth. This one fixes that by:
+//! - boiling every word down to (first letter, last letter, bitmask of covered letters),
+//!   so a search state is just (letter, coverage, depth) and the only thing on the
+//!   stack is the current path of dictionary indices
+//! - searching with a depth limit of 1 word, then 2, ... up to max_words. The first
+//!   solution found is therefore one with the fewest words possible (i.e. optimal).
+//! - pruning any state which can't possibly finish in the words it has left:
+//!     - if some uncovered letter isn't reachable at all from the current letter
+//!     - if there are more uncovered letters than (words left * most letters any word covers)
+//! - remembering dead (letter, coverage, depth) states. If a state can't be finished in
+//!   d words, it can't be finished in fewer either, so we only store the deepest failure.
+
+use log::{info, warn};
+use std::collections::HashMap;
+
+use super::SolverStrategy;
+use crate::dictionary::smart_dict;
+use crate::{LBPuzzle, LBPuzzleSolution};
+
+/// a dictionary word, reduced to what the search actually needs
+struct _Edge {
+    // stable dictionary index, per SmartDictionary::get_flat_indexed()
+    idx: usize,
+    // index of the word's last letter in the puzzle
+    last: usize,
+    // bitmask of all the puzzle letters this word covers
+    mask: u64,
+}
+
+/// the word graph for a single puzzle + dictionary
+struct _Graph {
+    // the puzzle's letters, in order. a letter's index here is its bit in the masks.
+    letters: Vec<char>,
+    // outgoing edges, indexed by the index of their first letter
+    edges: Vec<Vec<_Edge>>,
+    // for each letter, every letter coverable by some sequence of words starting there
+    reach: Vec<u64>,
+    // the most letters any single word covers
+    max_cover: u32,
+    // coverage of a finished puzzle
+    full: u64,
+}
+
+impl _Graph {
+    /// returns None if the puzzle has too many letters to fit in a bitmask
+    fn new<const L: usize, const S: usize>(
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+    ) -> Option<Self> {
+        let letters: Vec<char> = puzzle.all_letters().chars().collect();
+        if letters.len() > u64::BITS as usize {
+            return None;
+        }
+        let letter_idx = |c: char| _letter_idx(&letters, c);
+
+        let mut edges: Vec<Vec<_Edge>> = (0..letters.len()).map(|_| Vec::new()).collect();
+        let mut full = 0u64;
+        for &c in &letters {
+            full |= 1 << letter_idx(c)?;
+        }
+        let mut max_cover = 0;
+        for (idx, word) in dict.get_flat_indexed() {
+            // the smart dictionary only contains words made of puzzle letters, so these can't fail
+            let first = letter_idx(word.chars().next()?)?;
+            let last = letter_idx(word.chars().last()?)?;
+            let mut mask = 0u64;
+            for c in word.chars() {
+                mask |= 1 << letter_idx(c)?;
+            }
+            max_cover = max_cover.max(mask.count_ones());
+            edges[first].push(_Edge {
+                idx: *idx,
+                last,
+                mask,
+            });
+        }
+        // try the words which cover the most first
+        for letter_edges in edges.iter_mut() {
+            letter_edges.sort_by_key(|e| std::cmp::Reverse(e.mask.count_ones()));
+        }
+
+        // reach(l) = union of (mask(e) | reach(last(e))) over all edges e out of l.
+        // just iterate until nothing changes; there are at most 64 letters.
+        let mut reach = vec![0u64; letters.len()];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (l, letter_edges) in edges.iter().enumerate() {
+                let new_reach = letter_edges
+                    .iter()
+                    .fold(reach[l], |acc, e| acc | e.mask | reach[e.last]);
+                if new_reach != reach[l] {
+                    reach[l] = new_reach;
+                    changed = true;
+                }
+            }
+        }
+
+        Some(Self {
+            letters,
+            edges,
+            reach,
+            max_cover,
+            full,
+        })
+    }
+
+    /// the letter & coverage a partial solution leaves us at
+    fn state_after(&self, prefix: &[String]) -> (Option<usize>, u64) {
+        let letter = prefix
+            .last()
+            .and_then(|w| w.chars().last())
+            .and_then(|c| _letter_idx(&self.letters, c));
+        let coverage = prefix
+            .iter()
+            .flat_map(|w| w.chars())
+            .filter_map(|c| _letter_idx(&self.letters, c))
+            .fold(0u64, |acc, l| acc | (1 << l));
+        (letter, coverage)
+    }
+}
+
+fn _letter_idx(letters: &[char], c: char) -> Option<usize> {
+    letters.iter().position(|&l| l == c)
+}
+
+/// Finds an optimal (fewest words) solution with iterative-deepening DFS.
+/// See the module docs for details.
+pub struct IddfsSolver<const L: usize, const S: usize> {}
+
+impl<const L: usize, const S: usize> SolverStrategy<L, S> for IddfsSolver<L, S> {
+    fn solve_remainder(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+        prefix: &[String],
+    ) -> Option<LBPuzzleSolution> {
+        self._helper_from(puzzle, dict, prefix)
+    }
+}
+
+impl<const L: usize, const S: usize> IddfsSolver<L, S> {
+    /// Helper function for the search.
+    /// broken out separately for benchmarking purposes.
+    pub fn _helper(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+    ) -> Option<LBPuzzleSolution> {
+        self._helper_from(puzzle, dict, &[])
+    }
+
+    /// Same as _helper, but searches from wherever "prefix" leaves off.
+    /// See SolverStrategy::solve_remainder.
+    fn _helper_from(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+        prefix: &[String],
+    ) -> Option<LBPuzzleSolution> {
+        let graph = match _Graph::new(puzzle, dict) {
+            Some(graph) => graph,
+            None => {
+                warn!("IDDFS only supports puzzles with up to 64 letters.");
+                return None;
+            }
+        };
+
+        // dead states: (letter, coverage) -> the most words we've failed to finish it in.
+        // stays valid across depth limits, so it's shared between iterations.
+        let mut dead: HashMap<(Option<usize>, u64), usize> = HashMap::new();
+        let mut path: Vec<usize> = Vec::new();
+        let mut n_nodes_visited: u64 = 0;
+        let (letter, coverage) = graph.state_after(prefix);
+
+        // depth 0 only succeeds if the prefix already covers everything
+        for depth in 0..=puzzle.max_words().checked_sub(prefix.len())? {
+            if _search(
+                &graph,
+                letter,
+                coverage,
+                depth,
+                &mut path,
+                &mut dead,
+                &mut n_nodes_visited,
+            ) {
+                info!(
+                    "Found solution at depth {} | Nodes visited: {} | Dead states: {}",
+                    depth,
+                    n_nodes_visited,
+                    dead.len()
+                );
+                let word_path: Vec<String> = path
+                    .iter()
+                    .map(|idx| dict.get_word_by_idx(*idx).unwrap().as_ref().clone())
+                    .collect();
+                info!("Word path: {:?}", word_path);
+                return Some(word_path);
+            }
+        }
+
+        info!(
+            "No solution | Nodes visited: {} | Dead states: {}",
+            n_nodes_visited,
+            dead.len()
+        );
+        None
+    }
+
+    /// Count the optimal (fewest words) solutions, stopping early once there are "cap" of them.
+    /// Solutions are distinct sequences of words, so the same words in a different order count
+    /// separately.
+    ///
+    /// Returns None if there's no solution at all, else (words per optimal solution, count),
+    /// where the count is at most "cap".
+    pub fn count_optimal_solutions(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+        cap: usize,
+    ) -> Option<(usize, usize)> {
+        let graph = _Graph::new(puzzle, dict)?;
+        // (letter, coverage, words left) -> number of ways to finish, up to cap.
+        // like the dead states in _search, this is shared between depth limits.
+        let mut memo: HashMap<(Option<usize>, u64, usize), usize> = HashMap::new();
+        for depth in 1..=puzzle.max_words() {
+            let count = _count(&graph, None, 0, depth, cap, &mut memo);
+            if count > 0 {
+                info!("{} solutions with {} words (cap {})", count, depth, cap);
+                return Some((depth, count));
+            }
+        }
+        None
+    }
+
+    /// Find solutions with exactly "n_words" words, stopping early once there are "cap" of them.
+    /// Like count_optimal_solutions, the same words in a different order count separately.
+    /// A sequence which covers everything before its last word isn't a solution, since the game
+    /// would've ended already.
+    pub fn find_solutions(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+        n_words: usize,
+        cap: usize,
+    ) -> Vec<LBPuzzleSolution> {
+        let graph = match _Graph::new(puzzle, dict) {
+            Some(graph) => graph,
+            None => {
+                warn!("IDDFS only supports puzzles with up to 64 letters.");
+                return Vec::new();
+            }
+        };
+        let mut memo = HashMap::new();
+        let mut path = Vec::new();
+        let mut found = Vec::new();
+        _enumerate(
+            &graph, None, 0, n_words, cap, &mut path, &mut found, &mut memo,
+        );
+        info!(
+            "Found {} solutions with {} words (cap {})",
+            found.len(),
+            n_words,
+            cap
+        );
+        found
+            .iter()
+            .map(|path| {
+                path.iter()
+                    .map(|idx| dict.get_word_by_idx(*idx).unwrap().as_ref().clone())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// depth-limited DFS from a single state.
+/// returns true if it found a solution, in which case it's left in "path".
+fn _search(
+    graph: &_Graph,
+    letter: Option<usize>,
+    coverage: u64,
+    words_left: usize,
+    path: &mut Vec<usize>,
+    dead: &mut HashMap<(Option<usize>, u64), usize>,
+    n_nodes_visited: &mut u64,
+) -> bool {
+    *n_nodes_visited += 1;
+
+    // base cases: we're done, or we're out of words
+    if coverage == graph.full {
+        return true;
+    }
+    if words_left == 0 {
+        return false;
+    }
+
+    // bounds: can we possibly finish from here?
+    let uncovered = graph.full & !coverage;
+    if let Some(l) = letter {
+        if uncovered & !graph.reach[l] != 0 {
+            return false;
+        }
+    }
+    if uncovered.count_ones() as usize > words_left * graph.max_cover as usize {
+        return false;
+    }
+    if dead
+        .get(&(letter, coverage))
+        .is_some_and(|&d| d >= words_left)
+    {
+        return false;
+    }
+
+    // recurse over every word we could play next
+    let candidates: Box<dyn Iterator<Item = &_Edge>> = match letter {
+        Some(l) => Box::new(graph.edges[l].iter()),
+        None => Box::new(graph.edges.iter().flatten()),
+    };
+    for edge in candidates {
+        path.push(edge.idx);
+        if _search(
+            graph,
+            Some(edge.last),
+            coverage | edge.mask,
+            words_left - 1,
+            path,
+            dead,
+            n_nodes_visited,
+        ) {
+            return true;
+        }
+        path.pop();
+    }
+
+    // nothing worked, so remember that
+    let d = dead.entry((letter, coverage)).or_insert(0);
+    *d = (*d).max(words_left);
+    false
+}
+
+/// the number of ways to cover everything using exactly "words_left" more words, up to "cap".
+/// ways which cover everything early don't count.
+fn _count(
+    graph: &_Graph,
+    letter: Option<usize>,
+    coverage: u64,
+    words_left: usize,
+    cap: usize,
+    memo: &mut HashMap<(Option<usize>, u64, usize), usize>,
+) -> usize {
+    if words_left == 0 || coverage == graph.full {
+        return (words_left == 0 && coverage == graph.full) as usize;
+    }
+    // same bounds as _search
+    let uncovered = graph.full & !coverage;
+    if let Some(l) = letter {
+        if uncovered & !graph.reach[l] != 0 {
+            return 0;
+        }
+    }
+    if uncovered.count_ones() as usize > words_left * graph.max_cover as usize {
+        return 0;
+    }
+    if let Some(&count) = memo.get(&(letter, coverage, words_left)) {
+        return count;
+    }
+
+    let candidates: Box<dyn Iterator<Item = &_Edge>> = match letter {
+        Some(l) => Box::new(graph.edges[l].iter()),
+        None => Box::new(graph.edges.iter().flatten()),
+    };
+    let mut count = 0;
+    for edge in candidates {
+        count += _count(
+            graph,
+            Some(edge.last),
+            coverage | edge.mask,
+            words_left - 1,
+            cap,
+            memo,
+        );
+        if count >= cap {
+            count = cap;
+            break;
+        }
+    }
+    memo.insert((letter, coverage, words_left), count);
+    count
+}
+
+/// Collect the paths counted by _count into "found", until there are "cap" of them.
+/// _count does the pruning, so we only ever walk down branches with a solution at the end.
+#[allow(clippy::too_many_arguments)]
+fn _enumerate(
+    graph: &_Graph,
+    letter: Option<usize>,
+    coverage: u64,
+    words_left: usize,
+    cap: usize,
+    path: &mut Vec<usize>,
+    found: &mut Vec<Vec<usize>>,
+    memo: &mut HashMap<(Option<usize>, u64, usize), usize>,
+) {
+    if words_left == 0 {
+        if coverage == graph.full {
+            found.push(path.clone());
+        }
+        return;
+    }
+    let candidates: Box<dyn Iterator<Item = &_Edge>> = match letter {
+        Some(l) => Box::new(graph.edges[l].iter()),
+        None => Box::new(graph.edges.iter().flatten()),
+    };
+    for edge in candidates {
+        if found.len() >= cap {
+            return;
+        }
+        let next = coverage | edge.mask;
+        // cap 1 is enough to know whether there's anything down there
+        if _count(graph, Some(edge.last), next, words_left - 1, 1, memo) == 0 {
+            continue;
+        }
+        path.push(edge.idx);
+        _enumerate(
+            graph,
+            Some(edge.last),
+            next,
+            words_left - 1,
+            cap,
+            path,
+            found,
+            memo,
+        );
+        path.pop();
+    }
+}
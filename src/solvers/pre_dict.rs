@@ -17,9 +17,14 @@ use super::SolverStrategy;
 pub struct PreDictSolver<const L: usize, const S: usize> {}
 
 impl<const L: usize, const S: usize> SolverStrategy<L, S> for PreDictSolver<L, S> {
-    fn solve(&self, puzzle: &LBPuzzle<L, S>) -> Option<LBPuzzleSolution> {
-        let dict = smart_dict::SmartDictionary::new(&puzzle);
-        _solve_helper(&dict, puzzle, LBPuzzleSolution::new())
+    fn solve_remainder(
+        &self,
+        puzzle: &LBPuzzle<L, S>,
+        dict: &smart_dict::SmartDictionary,
+        prefix: &[String],
+    ) -> Option<LBPuzzleSolution> {
+        let mut solution = _solve_helper(dict, puzzle, prefix.to_vec())?;
+        Some(solution.split_off(prefix.len()))
     }
 }
 
@@ -51,18 +56,14 @@ fn _solve_helper<const L: usize, const S: usize>(
                 .chars()
                 .last()
                 .expect("Shouldn't get an empty word here.");
-            let words = dict.get(last_char);
             // if there's nothing under this letter, then this solution is a dead end--return none.
-            if words.is_none() {
-                return None;
-            }
-            words.unwrap()
+            dict.get(last_char)?
         }
     };
 
     // now go through all those words & see if they make a solution.
     for word in matching_words {
-        if words.contains(&word) {
+        if words.contains(word) {
             // we don't want any repeat words, cuz they're useless
             continue;
         }
@@ -0,0 +1,194 @@
+//! Look up solvers by name, so the CLI, benchmarks & tests don't each need their own list.
+//!
+//! A solver spec is a name, optionally followed by a colon & comma-separated options:
+//! - `astar`
+//! - `astar:weight=0.5,heuristic=lookahead`
+//! - `astar-fast:objective=letters`
+//!
+//! Only the A* solvers take options for now:
+//! - `weight`: the edge weight factor, between 0 and 1. See AStarSolver for details.
+//! - `heuristic`: one of `coverage`, `max-new`, `lookahead`. See a_star::Heuristic.
+//! - `objective`: one of `words`, `letters`, `rarity`. See a_star::Objective.
+
+use super::a_star::{AStarSolver, Heuristic, Objective};
+use super::brute_force::BruteForceSolver;
+use super::iddfs::IddfsSolver;
+use super::pre_dict::PreDictSolver;
+use super::SolverStrategy;
+use crate::LBPuzzleError::SolverConfigError;
+use crate::Result;
+
+/// every registered solver name, alongside a short description of it
+pub const SOLVERS: [(&str, &str); 5] = [
+    ("astar", "A* search. Optimal, and fast. (default)"),
+    (
+        "astar-fast",
+        "A* search with a low edge weight. Even faster, but not always optimal.",
+    ),
+    (
+        "iddfs",
+        "Iterative-deepening DFS. Optimal, and uses very little memory.",
+    ),
+    (
+        "predict",
+        "Greedy DFS over the precomputed dictionary. Not optimal, and eats memory.",
+    ),
+    ("brute", "Letter-by-letter brute force. Optimal, but slow."),
+];
+
+/// the solver used when nobody asks for a specific one
+pub const DEFAULT_SOLVER: &str = "astar";
+
+/// edge weight factor for "astar-fast"
+const FAST_EDGE_WEIGHT_FACTOR: f32 = 0.1;
+
+/// construct a solver from a spec like "astar:weight=0.5" (see module docs)
+pub fn get_solver<const L: usize, const S: usize>(
+    spec: &str,
+) -> Result<Box<dyn SolverStrategy<L, S>>> {
+    let (name, options) = match spec.split_once(':') {
+        Some((name, options)) => (name, parse_options(options)?),
+        None => (spec, Vec::new()),
+    };
+
+    match name.trim() {
+        "astar" => a_star_from_options(1.0, &options),
+        "astar-fast" => a_star_from_options(FAST_EDGE_WEIGHT_FACTOR, &options),
+        "iddfs" => no_options(name, &options, Box::new(IddfsSolver {})),
+        "predict" => no_options(name, &options, Box::new(PreDictSolver {})),
+        "brute" => no_options(name, &options, Box::new(BruteForceSolver {})),
+        _ => Err(SolverConfigError(format!(
+            "Unknown solver \"{}\". Options are: {}",
+            name,
+            SOLVERS.map(|(n, _)| n).join(", ")
+        ))),
+    }
+}
+
+/// splits "a=1,b=2" into [("a", "1"), ("b", "2")]
+fn parse_options(options: &str) -> Result<Vec<(&str, &str)>> {
+    options
+        .split(',')
+        .filter(|o| !o.trim().is_empty())
+        .map(|o| match o.split_once('=') {
+            Some((key, value)) => Ok((key.trim(), value.trim())),
+            None => Err(SolverConfigError(format!(
+                "Solver option \"{}\" should look like key=value",
+                o
+            ))),
+        })
+        .collect()
+}
+
+fn no_options<const L: usize, const S: usize>(
+    name: &str,
+    options: &[(&str, &str)],
+    solver: Box<dyn SolverStrategy<L, S>>,
+) -> Result<Box<dyn SolverStrategy<L, S>>> {
+    if let Some((key, _)) = options.first() {
+        return Err(SolverConfigError(format!(
+            "Solver \"{}\" doesn't take any options (got \"{}\")",
+            name, key
+        )));
+    }
+    Ok(solver)
+}
+
+fn a_star_from_options<const L: usize, const S: usize>(
+    default_weight: f32,
+    options: &[(&str, &str)],
+) -> Result<Box<dyn SolverStrategy<L, S>>> {
+    let mut weight = default_weight;
+    let mut heuristic = Heuristic::default();
+    let mut objective = Objective::default();
+
+    for &(key, value) in options {
+        match key {
+            "weight" => {
+                weight = match value.parse::<f32>() {
+                    Ok(w) if (0.0..=1.0).contains(&w) => w,
+                    _ => {
+                        return Err(SolverConfigError(format!(
+                            "weight should be a number between 0 and 1 (got \"{}\")",
+                            value
+                        )))
+                    }
+                }
+            }
+            "heuristic" => {
+                heuristic = match value {
+                    "coverage" => Heuristic::Coverage,
+                    "max-new" => Heuristic::MaxNewLetters,
+                    "lookahead" => Heuristic::Lookahead,
+                    _ => {
+                        return Err(SolverConfigError(format!(
+                            "heuristic should be one of coverage, max-new, lookahead (got \"{}\")",
+                            value
+                        )))
+                    }
+                }
+            }
+            "objective" => {
+                objective = match value {
+                    "words" => Objective::WordCount,
+                    "letters" => Objective::WordsThenLetters,
+                    "rarity" => Objective::WordsThenRarity,
+                    _ => {
+                        return Err(SolverConfigError(format!(
+                            "objective should be one of words, letters, rarity (got \"{}\")",
+                            value
+                        )))
+                    }
+                }
+            }
+            _ => {
+                return Err(SolverConfigError(format!(
+                    "Unknown A* option \"{}\". Options are: weight, heuristic, objective",
+                    key
+                )))
+            }
+        }
+    }
+
+    Ok(Box::new(
+        AStarSolver::new(weight)
+            .with_heuristic(heuristic)
+            .with_objective(objective),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_solver, SOLVERS};
+    use crate::NYTBoxPuzzle;
+
+    #[test]
+    fn test_get_solver() {
+        for (name, _) in SOLVERS {
+            assert!(get_solver::<4, 3>(name).is_ok());
+        }
+        assert!(get_solver::<4, 3>("astar:weight=0.5,heuristic=lookahead").is_ok());
+        assert!(get_solver::<4, 3>("astar-fast:objective=letters,").is_ok());
+
+        let bad_specs = [
+            "",
+            "dijkstra",
+            "astar:weight",
+            "astar:weight=2",
+            "astar:heuristic=magic",
+            "astar:color=blue",
+            "iddfs:weight=0.5",
+        ];
+        for spec in bad_specs {
+            assert!(get_solver::<4, 3>(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn test_registered_solver_solves() {
+        let puzzle = NYTBoxPuzzle::from_str(5, "lei xys vuc tok").unwrap();
+        let solver = get_solver("astar:heuristic=max-new").unwrap();
+        let solution = solver.solve(&puzzle).unwrap();
+        assert!(puzzle.validate_solution(&solution).is_ok());
+    }
+}
@@ -0,0 +1,167 @@
+//! Exports a puzzle, and optionally a solution, as an SVG image.
+//!
+//! The puzzle is drawn as a regular polygon (a square for NYT puzzles) with a dot on the edge for
+//! every letter, and the letter itself just outside. A solution is drawn as one colored line per
+//! word, hopping between the dots. Letters the solution uses get filled dots.
+//!
+//! Uses the same layout as the text renderer (see the render module), so it works for any S & L.
+//! All numbers are printed with fixed precision, so the output is deterministic & can be
+//! snapshot-tested.
+
+use crate::render::{_Layout, _Point, _letter_positions};
+use crate::{LBPuzzle, LBPuzzleSolution};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// colors for each word of a solution, cycling if there are more words than colors
+const WORD_COLORS: [&str; 6] = [
+    "#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4",
+];
+
+/// pixels from the center to each corner, per letter on a side
+const RADIUS_PER_LETTER: f64 = 40.0;
+/// pixels between the edge & the middle of each letter
+const LABEL_OFFSET: f64 = 24.0;
+/// pixels of empty space around the drawing
+const MARGIN: f64 = 16.0;
+const DOT_RADIUS: f64 = 6.0;
+const FONT_SIZE: f64 = 22.0;
+
+/// Draw the puzzle as an SVG document, with the solution on top if there is one.
+/// Like the text renderer, it stops drawing a solution at the first hop that isn't on the board.
+pub fn to_svg<const S: usize, const L: usize>(
+    puzzle: &LBPuzzle<S, L>,
+    solution: Option<&LBPuzzleSolution>,
+) -> String {
+    let radius = RADIUS_PER_LETTER * (L + 1) as f64;
+    let layout = _Layout::new::<S, L>(radius, LABEL_OFFSET, 0.0);
+    // the layout is centered on (0, 0), so shift everything into the positive quadrant
+    let half = radius + LABEL_OFFSET + FONT_SIZE / 2.0 + MARGIN;
+    let size = 2.0 * half;
+    let at = |p: _Point| format!("{:.1},{:.1}", p.x + half, p.y + half);
+
+    let mut svg = String::new();
+    // writing to a String can't fail, so the results are ignored below
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{size:.0}" height="{size:.0}" viewBox="0 0 {size:.0} {size:.0}">"#
+    );
+    let _ = writeln!(svg, r#"  <rect width="100%" height="100%" fill="white"/>"#);
+    let corners: Vec<String> = layout.corners.iter().map(|&p| at(p)).collect();
+    let _ = writeln!(
+        svg,
+        r#"  <polygon points="{}" fill="none" stroke="black" stroke-width="3"/>"#,
+        corners.join(" ")
+    );
+
+    // one line per word. consecutive words share a letter, so each line starts where the last ended.
+    let solution = solution.cloned().unwrap_or_default();
+    let positions = _letter_positions(puzzle, &solution);
+    let mut start = 0;
+    for (i, word) in solution.iter().enumerate() {
+        let end = (start + word.chars().count()).min(positions.len());
+        if end <= start + 1 {
+            break;
+        }
+        let points: Vec<String> = positions[start..end]
+            .iter()
+            .map(|&(side, j)| at(layout.anchors[side][j]))
+            .collect();
+        let _ = writeln!(
+            svg,
+            r#"  <polyline points="{}" fill="none" stroke="{}" stroke-width="4" stroke-linejoin="round" stroke-linecap="round"/>"#,
+            points.join(" "),
+            WORD_COLORS[i % WORD_COLORS.len()]
+        );
+        start = end - 1;
+    }
+
+    let covered: BTreeSet<char> = solution.iter().flat_map(|w| w.chars()).collect();
+    for (side, (anchors, labels)) in puzzle
+        .sides()
+        .iter()
+        .zip(layout.anchors.iter().zip(&layout.labels))
+    {
+        for (letter, (&anchor, &label)) in side.iter().zip(anchors.iter().zip(labels)) {
+            let fill = match covered.contains(letter) {
+                true => "black",
+                false => "white",
+            };
+            let (x, y) = (anchor.x + half, anchor.y + half);
+            let _ = writeln!(
+                svg,
+                r#"  <circle cx="{x:.1}" cy="{y:.1}" r="{DOT_RADIUS:.1}" fill="{fill}" stroke="black" stroke-width="2"/>"#
+            );
+            let (x, y) = (label.x + half, label.y + half);
+            let _ = writeln!(
+                svg,
+                r#"  <text x="{x:.1}" y="{y:.1}" font-family="sans-serif" font-size="{FONT_SIZE:.0}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                _escape(&letter.to_uppercase().to_string())
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// escape anything XML would choke on
+fn _escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_svg;
+    use crate::{LBPuzzle, NYTBoxPuzzle};
+
+    #[test]
+    fn test_to_svg() {
+        let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+        let solution = vec!["juvenile".to_string(), "embark".to_string()];
+        let svg = to_svg(&puzzle, Some(&solution));
+        println!("{}", svg);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        // one line per word, and every letter is used
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert_eq!(svg.matches(r#"fill="black" stroke="black""#).count(), 12);
+        // same input, same output
+        assert_eq!(svg, to_svg(&puzzle, Some(&solution)));
+
+        // a partial solution only fills in what it uses
+        let svg = to_svg(&puzzle, Some(&vec!["bum".to_string()]));
+        assert_eq!(svg.matches("<polyline").count(), 1);
+        assert_eq!(svg.matches(r#"fill="black" stroke="black""#).count(), 3);
+    }
+
+    #[test]
+    fn test_to_svg_other_shapes() {
+        let triangle = LBPuzzle::<3, 2>::from_str(3, "a< cd ef").unwrap();
+        let svg = to_svg(&triangle, None);
+        println!("{}", svg);
+        assert_eq!(
+            svg,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="342" height="342" viewBox="0 0 342 342">
+  <rect width="100%" height="100%" fill="white"/>
+  <polygon points="67.1,111.0 274.9,111.0 171.0,291.0" fill="none" stroke="black" stroke-width="3"/>
+  <circle cx="136.4" cy="111.0" r="6.0" fill="white" stroke="black" stroke-width="2"/>
+  <text x="136.4" y="87.0" font-family="sans-serif" font-size="22" text-anchor="middle" dominant-baseline="central">A</text>
+  <circle cx="205.6" cy="111.0" r="6.0" fill="white" stroke="black" stroke-width="2"/>
+  <text x="205.6" y="87.0" font-family="sans-serif" font-size="22" text-anchor="middle" dominant-baseline="central">&lt;</text>
+  <circle cx="240.3" cy="171.0" r="6.0" fill="white" stroke="black" stroke-width="2"/>
+  <text x="261.1" y="183.0" font-family="sans-serif" font-size="22" text-anchor="middle" dominant-baseline="central">C</text>
+  <circle cx="205.6" cy="231.0" r="6.0" fill="white" stroke="black" stroke-width="2"/>
+  <text x="226.4" y="243.0" font-family="sans-serif" font-size="22" text-anchor="middle" dominant-baseline="central">D</text>
+  <circle cx="101.7" cy="171.0" r="6.0" fill="white" stroke="black" stroke-width="2"/>
+  <text x="80.9" y="183.0" font-family="sans-serif" font-size="22" text-anchor="middle" dominant-baseline="central">E</text>
+  <circle cx="136.4" cy="231.0" r="6.0" fill="white" stroke="black" stroke-width="2"/>
+  <text x="115.6" y="243.0" font-family="sans-serif" font-size="22" text-anchor="middle" dominant-baseline="central">F</text>
+</svg>
+"##
+        );
+    }
+}
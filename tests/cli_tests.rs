@@ -0,0 +1,276 @@
+// CLI tests: run the actual binary, & check its output & exit codes
+
+use assert_cmd::Command;
+use predicates::str::contains;
+use serde_json::Value;
+use std::path::PathBuf;
+
+const PUZZLE: &str = "erb uln imk jav"; // nov 6, 2024
+
+fn cli() -> Command {
+    Command::cargo_bin("letterboxed_solver").unwrap()
+}
+
+/// our own dictionary, so we know exactly which words are in it (& it's quick to load).
+/// the name just has to be unique per test, since tests run in parallel.
+fn temp_dict(name: &str) -> PathBuf {
+    let dict = std::env::temp_dir().join(format!("letterboxed_cli_test_{}.txt", name));
+    std::fs::write(&dict, "juvenile\nembark\n").unwrap();
+    dict
+}
+
+/// the keys of a JSON object, in (serde_json's) alphabetical order
+fn keys(value: &Value) -> Vec<&str> {
+    value
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(|k| k.as_str())
+        .collect()
+}
+
+fn parse_json(output: &[u8]) -> Value {
+    serde_json::from_slice(output).unwrap()
+}
+
+#[test]
+fn test_solve() {
+    let output = cli()
+        .args(["solve", PUZZLE, "6", "--format", "plain"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let words: Vec<String> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|w| w.to_string())
+        .collect();
+    assert!(!words.is_empty());
+
+    // whatever it found had better pass validation
+    cli()
+        .args(["validate", PUZZLE, "6"])
+        .args(&words)
+        .assert()
+        .success()
+        .stdout(contains("VALID"));
+}
+
+#[test]
+fn test_solve_no_solution() {
+    // only 1 word allowed, & there's no 12-letter word covering the whole board
+    cli()
+        .args(["solve", PUZZLE, "1"])
+        .assert()
+        .code(1)
+        .stderr(contains("No solution found!"));
+}
+
+#[test]
+fn test_validate() {
+    let dict = temp_dict("validate");
+    let validate = |words: &[&str]| {
+        let mut cmd = cli();
+        cmd.args(["validate", PUZZLE, "6"])
+            .args(words)
+            .arg("--dict")
+            .arg(&dict);
+        cmd
+    };
+
+    validate(&["juvenile", "embark"])
+        .assert()
+        .success()
+        .stdout(contains("VALID"));
+    validate(&["juvenile", "embark", "--format", "plain"])
+        .assert()
+        .success()
+        .stdout("valid\n");
+    validate(&["juvenile"])
+        .assert()
+        .code(1)
+        .stdout(contains("INVALID"));
+    validate(&["juvenile", "emubark"])
+        .assert()
+        .code(1)
+        .stdout(contains("isn't in the dictionary"));
+}
+
+#[test]
+fn test_bad_input() {
+    // not enough sides
+    cli()
+        .args(["solve", "erb uln imk", "6"])
+        .assert()
+        .code(2)
+        .stderr(contains("Invalid puzzle"));
+    // not a number
+    cli().args(["solve", PUZZLE, "six"]).assert().code(2);
+    // not a solver
+    cli()
+        .args(["solve", PUZZLE, "6", "--solver", "dijkstra"])
+        .assert()
+        .code(2);
+    // not a dictionary
+    cli()
+        .args([
+            "validate",
+            PUZZLE,
+            "6",
+            "juvenile",
+            "--dict",
+            "no/such/dictionary.txt",
+        ])
+        .assert()
+        .code(2)
+        .stderr(contains("Couldn't open dictionary"));
+}
+
+// the JSON output is for scripts, so any change to its keys should be on purpose (along with a bump
+// to schema_version, & the README's schema table)
+
+const SOLVE_KEYS: [&str; 12] = [
+    "diagnosis",
+    "dictionary",
+    "elapsed_ms",
+    "letter_count",
+    "max_words",
+    "puzzle",
+    "schema_version",
+    "sides",
+    "solution",
+    "solved",
+    "solver",
+    "word_count",
+];
+
+#[test]
+fn test_solve_json_keys() {
+    let dict = temp_dict("solve_json");
+    let output = cli()
+        .args(["solve", PUZZLE, "6", "--format", "json", "--dict"])
+        .arg(&dict)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let value = parse_json(&output.stdout);
+    assert_eq!(keys(&value), SOLVE_KEYS);
+    assert_eq!(value["schema_version"], 2);
+    assert_eq!(value["solution"], serde_json::json!(["juvenile", "embark"]));
+    assert!(value["diagnosis"].is_null());
+
+    // no solution: same keys, plus a diagnosis
+    let output = cli()
+        .args(["solve", PUZZLE, "1", "--format", "json", "--dict"])
+        .arg(&dict)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let value = parse_json(&output.stdout);
+    assert_eq!(keys(&value), SOLVE_KEYS);
+    assert!(value["solution"].is_null());
+    assert_eq!(
+        keys(&value["diagnosis"]),
+        [
+            "dead_end_letters",
+            "last_word_only_letters",
+            "missing_letters",
+            "nearest_miss",
+            "reasons",
+            "uncoverable_letters",
+        ]
+    );
+}
+
+#[test]
+fn test_validate_json_keys() {
+    let dict = temp_dict("validate_json");
+    let output = cli()
+        .args([
+            "validate", PUZZLE, "6", "juvenile", "--format", "json", "--dict",
+        ])
+        .arg(&dict)
+        .output()
+        .unwrap();
+    let value = parse_json(&output.stdout);
+    assert_eq!(
+        keys(&value),
+        ["problems", "puzzle", "schema_version", "valid", "words"]
+    );
+    assert_eq!(value["valid"], false);
+}
+
+#[test]
+fn test_batch_json_keys() {
+    let dict = temp_dict("batch_json");
+    let input = "erb uln imk jav 6 2024-11-06\nerb uln 6\n";
+    let summary_keys = ["average_words", "invalid", "solved", "total_ms", "unsolved"];
+    let error_keys = ["error", "input", "line", "schema_version"];
+    let mut result_keys: Vec<&str> = SOLVE_KEYS
+        .iter()
+        .filter(|k| **k != "diagnosis")
+        .copied()
+        .collect();
+    result_keys.extend(["date", "line"]);
+    result_keys.sort();
+
+    // json: everything in one object
+    let output = cli()
+        .args(["batch", "-", "--format", "json", "--dict"])
+        .arg(&dict)
+        .write_stdin(input)
+        .output()
+        .unwrap();
+    let value = parse_json(&output.stdout);
+    assert_eq!(keys(&value), ["results", "schema_version", "summary"]);
+    assert_eq!(keys(&value["results"][0]), result_keys);
+    assert_eq!(keys(&value["results"][1]), error_keys);
+    assert_eq!(keys(&value["summary"]), summary_keys);
+
+    // ndjson: one line per result, then the summary
+    let output = cli()
+        .args(["batch", "-", "--format", "ndjson", "--dict"])
+        .arg(&dict)
+        .write_stdin(input)
+        .output()
+        .unwrap();
+    let lines: Vec<Value> = output
+        .stdout
+        .split(|b| *b == b'\n')
+        .filter(|l| !l.is_empty())
+        .map(parse_json)
+        .collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(keys(&lines[0]), result_keys);
+    assert_eq!(keys(&lines[1]), error_keys);
+    assert_eq!(keys(&lines[2]), ["schema_version", "summary"]);
+    assert_eq!(keys(&lines[2]["summary"]), summary_keys);
+}
+
+#[test]
+fn test_hint_json_keys() {
+    let dict = temp_dict("hint_json");
+    let output = cli()
+        .args([
+            "hint", PUZZLE, "6", "juvenile", "--format", "json", "--dict",
+        ])
+        .arg(&dict)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let value = parse_json(&output.stdout);
+    assert_eq!(
+        keys(&value),
+        [
+            "hint",
+            "level",
+            "puzzle",
+            "schema_version",
+            "word_count",
+            "words"
+        ]
+    );
+    // the word so far, plus embark
+    assert_eq!(value["words"], serde_json::json!(["juvenile"]));
+    assert_eq!(value["word_count"], 2);
+}
@@ -1,65 +1,324 @@
 // basic integration tests for the module
 
 // define some example input structs
-use letterboxed_solver::solvers::{a_star, brute_force, pre_dict, SolverStrategy};
+use letterboxed_solver::dictionary::smart_dict::SmartDictionary;
+use letterboxed_solver::solvers::{
+    a_star, brute_force, conformance, iddfs, registry, SolverStrategy,
+};
 use letterboxed_solver::NYTBoxPuzzle;
+use letterboxed_solver::{analysis, archive, compare, dictionary};
+use std::collections::HashSet;
 
-// todo: sure would be nice if I could have a helper function to test any algo...
-// will have to wait until i go back and refactor the algos using the Strategy pattern & refer
-// to them with a trait. (i think that's how I'd do it? still learning as i go.).
-// for now i'm just gonna copypasta these around.
-
+/// every puzzle in the bundled NYT archive
 fn get_test_puzzles() -> Vec<NYTBoxPuzzle> {
-    vec![
-        NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap(), // nov 6, 2024
-        NYTBoxPuzzle::from_str(5, "lei xys vuc tok").unwrap(), // dec 31, 2024
-        NYTBoxPuzzle::from_str(5, "ZMC NTI SRO GHA").unwrap(), // dec 30, 2024
-    ]
+    archive::nyt_puzzles().unwrap()
 }
 
-#[test]
-#[ignore] // because brute force takes soooooooo long
-fn test_brute_force() {
+/// solve every test puzzle with the solver from the registry & make sure each solution is valid.
+/// returns the solutions, in case the caller wants to check anything else about them.
+fn check_solver(spec: &str) -> Vec<Vec<String>> {
+    let solver = registry::get_solver(spec).unwrap();
+    let mut solutions = Vec::new();
     for puzzle in get_test_puzzles() {
-        println!("TEST CASE: {}", puzzle);
+        println!("TEST CASE: {} ({})", puzzle, spec);
 
-        let solver = brute_force::BruteForceSolver {};
         let solution = solver.solve(&puzzle);
 
         assert!(solution.is_some());
         let solution = solution.unwrap();
         println!("{:?}", solution);
         assert!(puzzle.validate_solution(&solution).is_ok());
+        solutions.push(solution);
     }
+    solutions
+}
+
+/// check that two solvers agree on the number of words for every test puzzle
+fn assert_same_lengths(a: &[Vec<String>], b: &[Vec<String>]) {
+    let lengths = |s: &[Vec<String>]| s.iter().map(|w| w.len()).collect::<Vec<usize>>();
+    assert_eq!(lengths(a), lengths(b));
+}
+
+#[test]
+#[ignore] // because brute force takes soooooooo long
+fn test_brute_force() {
+    // brute force is our reference oracle--A* should never beat it, or else one of them is wrong.
+    assert_same_lengths(&check_solver("brute"), &check_solver("astar"));
+}
+
+#[test]
+fn test_brute_force_one_puzzle() {
+    // same as above, but only for the archive puzzle brute force finishes quickest
+    let puzzle = NYTBoxPuzzle::from_str(5, "lei xys vuc tok").unwrap(); // dec 31, 2024
+    let brute = brute_force::BruteForceSolver {}.solve(&puzzle).unwrap();
+    assert!(puzzle.validate_solution(&brute).is_ok());
+    let a_star = a_star::AStarSolver::new(1.0).solve(&puzzle).unwrap();
+    assert_eq!(brute.len(), a_star.len());
 }
 
 #[test]
 #[ignore] // still eats too much memory! run benchmark instead
 fn test_pre_dict() {
+    check_solver("predict");
+}
+
+#[test]
+fn test_a_star() {
+    check_solver("astar");
+}
+
+#[test]
+fn test_a_star_fast() {
+    check_solver("astar-fast");
+}
+
+#[test]
+fn test_iddfs() {
+    // both are optimal, so they should agree on the number of words
+    assert_same_lengths(&check_solver("iddfs"), &check_solver("astar"));
+}
+
+#[test]
+fn test_conformance() {
+    // the conformance puzzles are tiny, so even predict can handle them
+    for (spec, optimal) in [
+        ("astar", true),
+        ("iddfs", true),
+        ("brute", true),
+        ("astar-fast", false),
+        ("predict", false),
+    ] {
+        println!("TEST CASE: {}", spec);
+        conformance::assert_conforms::<4, 3>(registry::get_solver(spec).unwrap().as_ref(), optimal);
+        conformance::assert_conforms::<3, 2>(registry::get_solver(spec).unwrap().as_ref(), optimal);
+    }
+}
+
+#[test]
+fn test_iddfs_count_optimal_solutions() {
+    let solver = iddfs::IddfsSolver {};
+    for puzzle in get_test_puzzles() {
+        let dict = SmartDictionary::new(&puzzle);
+        let solution = solver.solve_with_dict(&puzzle, &dict).unwrap();
+        let (words, count) = solver
+            .count_optimal_solutions(&puzzle, &dict, 1000)
+            .unwrap();
+        println!("{}: {} solutions with {} words", puzzle, count, words);
+        assert_eq!(words, solution.len());
+        assert!(count >= 1);
+        // listing them finds the same number, and they're all real solutions
+        let solutions = solver.find_solutions(&puzzle, &dict, words, 1000);
+        assert_eq!(solutions.len(), count);
+        for solution in &solutions {
+            assert!(puzzle.validate_solution(solution).is_ok());
+        }
+        // stops as soon as it hits the cap
+        assert_eq!(
+            solver.count_optimal_solutions(&puzzle, &dict, 1),
+            Some((words, 1))
+        );
+    }
+
+    // fewer words always wins, no matter how many longer solutions there are
+    let puzzle = NYTBoxPuzzle::from_str(2, "erb uln imk jav").unwrap();
+    let words = "juvenile\nembark\n";
+    let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+    assert_eq!(
+        solver.count_optimal_solutions(&puzzle, &dict, 10),
+        Some((2, 1))
+    );
+    let words = "juvenile\nembark\njuvenilembark\nembarkjuvenile\n";
+    let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+    assert_eq!(
+        solver.count_optimal_solutions(&puzzle, &dict, 10),
+        Some((1, 2))
+    );
+    assert_eq!(
+        solver.count_optimal_solutions(
+            &puzzle,
+            &SmartDictionary::new_from_file(&puzzle, "bum\n".as_bytes()),
+            10
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_a_star_heuristics_are_optimal() {
+    let heuristics = [
+        a_star::Heuristic::Coverage,
+        a_star::Heuristic::MaxNewLetters,
+        a_star::Heuristic::Lookahead,
+    ];
     for puzzle in get_test_puzzles() {
         println!("TEST CASE: {}", puzzle);
 
-        let solver = pre_dict::PreDictSolver {};
-        let solution = solver.solve(&puzzle);
+        // brute force is exhaustive, so it knows the true minimum
+        let oracle = brute_force::BruteForceSolver {}.solve(&puzzle).unwrap();
 
-        assert!(solution.is_some());
-        let solution = solution.unwrap();
-        println!("{:?}", solution);
-        assert!(puzzle.validate_solution(&solution).is_ok());
+        for heuristic in heuristics {
+            let solver = a_star::AStarSolver::new(1.0).with_heuristic(heuristic);
+            let solution = solver.solve(&puzzle);
+
+            assert!(solution.is_some());
+            let solution = solution.unwrap();
+            println!("{:?}: {:?}", heuristic, solution);
+            assert!(puzzle.validate_solution(&solution).is_ok());
+            assert_eq!(solution.len(), oracle.len());
+        }
     }
 }
 
 #[test]
-fn test_a_star() {
+fn test_a_star_fewest_letters() {
     for puzzle in get_test_puzzles() {
         println!("TEST CASE: {}", puzzle);
 
-        let solver = a_star::AStarSolver::new(1.0);
-        let solution = solver.solve(&puzzle);
-
-        assert!(solution.is_some());
-        let solution = solution.unwrap();
+        let solver =
+            a_star::AStarSolver::new(1.0).with_objective(a_star::Objective::WordsThenLetters);
+        let solution = solver.solve(&puzzle).unwrap();
         println!("{:?}", solution);
         assert!(puzzle.validate_solution(&solution).is_ok());
+
+        // same number of words as usual, but never more letters
+        let default_solution = a_star::AStarSolver::new(1.0).solve(&puzzle).unwrap();
+        assert_eq!(solution.len(), default_solution.len());
+        let n_letters = |s: &Vec<String>| s.iter().map(|w| w.len()).sum::<usize>();
+        assert!(n_letters(&solution) <= n_letters(&default_solution));
+    }
+}
+
+#[test]
+fn test_a_star_objectives() {
+    // a tiny made-up dictionary where we know all the solutions:
+    // - juvenile + emubark (15 letters, but emubark is more "common")
+    // - juvenile + embark (14 letters)
+    // plus a couple of valid words that don't lead anywhere.
+    let puzzle = NYTBoxPuzzle::from_str(6, "erb uln imk jav").unwrap();
+    let words = "juvenile\nemubark\nembark\nbum\nmane\n";
+    let dict = SmartDictionary::new_from_file(&puzzle, words.as_bytes());
+
+    let cases = [
+        (
+            a_star::Objective::WordsThenLetters,
+            vec!["juvenile", "embark"],
+        ),
+        (
+            a_star::Objective::WordsThenRarity,
+            vec!["juvenile", "emubark"],
+        ),
+    ];
+    for (objective, expected) in cases {
+        let solver = a_star::AStarSolver::new(1.0).with_objective(objective);
+        let solution = solver._helper(&puzzle, &dict);
+        println!("{:?}: {:?}", objective, solution);
+        assert_eq!(solution.unwrap(), expected);
+    }
+}
+
+#[test]
+fn test_solve_from() {
+    for puzzle in get_test_puzzles().into_iter().take(2) {
+        println!("TEST CASE: {}", puzzle);
+        let dict = SmartDictionary::new(&puzzle);
+        let optimal = registry::get_solver("astar")
+            .unwrap()
+            .solve_with_dict(&puzzle, &dict)
+            .unwrap();
+
+        for spec in ["astar", "iddfs", "brute"] {
+            let solver = registry::get_solver(spec).unwrap();
+
+            // starting with an optimal solution's first word, we can still finish optimally
+            let prefix = optimal[..1].to_vec();
+            let solution = solver.solve_from(&puzzle, &dict, &prefix).unwrap().unwrap();
+            println!("{}: {:?}", spec, solution);
+            assert!(puzzle.validate_solution(&solution).is_ok());
+            assert_eq!(solution[0], prefix[0]);
+            assert_eq!(solution.len(), optimal.len());
+
+            // a finished solution has nothing left to add
+            let solution = solver.solve_from(&puzzle, &dict, &optimal).unwrap();
+            assert_eq!(solution, Some(optimal.clone()));
+
+            // nonsense isn't a valid start
+            let prefix = vec!["qqq".to_string()];
+            assert!(solver.solve_from(&puzzle, &dict, &prefix).is_err());
+        }
     }
 }
+
+#[test]
+fn test_rate_archive() {
+    for entry in archive::load_nyt_archive().unwrap() {
+        let dict = SmartDictionary::new(&entry.puzzle);
+        let rating = analysis::rate_difficulty(&entry.puzzle, &dict);
+        println!(
+            "{} {}: {} ({:.0})",
+            entry.date, entry.puzzle, rating.difficulty, rating.score
+        );
+        assert!((0.0..=100.0).contains(&rating.score));
+        // every archived puzzle is solvable with our dictionary
+        assert!(rating.features.optimal_words.is_some());
+    }
+}
+
+#[test]
+fn test_compare_archive() {
+    let entries = archive::load_nyt_archive().unwrap();
+    let mut dictionary_words = String::new();
+    std::io::Read::read_to_string(
+        &mut dictionary::get_default_dictionary_reader(),
+        &mut dictionary_words,
+    )
+    .unwrap();
+    let known: HashSet<&str> = dictionary_words.lines().collect();
+
+    let mut comparisons = Vec::new();
+    let summary =
+        compare::compare_with_answers(&entries, &dictionary_words, &iddfs::IddfsSolver {}, |c| {
+            comparisons.push(c.clone())
+        });
+
+    // one report per answered puzzle, in archive order
+    let answered: Vec<_> = entries.iter().filter(|e| e.answer.is_some()).collect();
+    assert!(!answered.is_empty());
+    assert_eq!(comparisons.len(), answered.len());
+    for (comparison, entry) in comparisons.iter().zip(&answered) {
+        assert_eq!(comparison.date, entry.date);
+        assert_eq!(Some(&comparison.official), entry.answer.as_ref());
+        // exactly the official words our dictionary lacks
+        let missing: Vec<&String> = comparison
+            .official
+            .iter()
+            .filter(|w| !known.contains(w.as_str()))
+            .collect();
+        assert_eq!(comparison.missing_words.iter().collect::<Vec<_>>(), missing);
+        assert_eq!(comparison.official_reachable(), missing.is_empty());
+        // every archived puzzle is solvable with our dictionary, & iddfs answers are optimal
+        let ours = comparison.ours.as_ref().unwrap();
+        assert!(entry.puzzle.validate_solution(ours).is_ok());
+        if comparison.official_reachable() {
+            assert!(comparison.as_short());
+        }
+    }
+
+    // nov 6 2024's official answer is juvenile embark, but embark isn't a common enough word,
+    // & without it we can't do it in two
+    let nov_6 = comparisons.iter().find(|c| c.date == "2024-11-06").unwrap();
+    assert_eq!(nov_6.missing_words, ["embark"]);
+    assert!(!nov_6.as_short());
+
+    assert_eq!(summary.compared, answered.len());
+    assert_eq!(
+        summary.reachable,
+        comparisons
+            .iter()
+            .filter(|c| c.official_reachable())
+            .count()
+    );
+    assert_eq!(
+        summary.as_short,
+        comparisons.iter().filter(|c| c.as_short()).count()
+    );
+}
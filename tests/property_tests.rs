@@ -0,0 +1,198 @@
+// property tests: random boards & small random dictionaries, checked against an exhaustive oracle
+
+use letterboxed_solver::dictionary::smart_dict::SmartDictionary;
+use letterboxed_solver::solvers::registry;
+use letterboxed_solver::LBPuzzle;
+use proptest::prelude::*;
+use std::collections::BTreeSet;
+
+const ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
+
+/// solvers which promise the fewest words possible
+const OPTIMAL_SOLVERS: [&str; 3] = ["astar", "iddfs", "brute"];
+/// solvers which only promise *a* solution
+const OTHER_SOLVERS: [&str; 1] = ["astar-fast"];
+
+/// A random board with S*L distinct letters, plus a small dictionary for it.
+/// Most words are random walks around the board, so they're playable, but some are spelled with
+/// any letters at all (including some that aren't on the board), so the dictionary filter has
+/// something to do.
+fn board_and_words<const S: usize, const L: usize>(
+) -> impl Strategy<Value = (String, usize, Vec<String>)> {
+    let letters: Vec<char> = ALPHABET.chars().collect();
+    let n_letters = (S * L + 2).min(letters.len());
+    // a walk is a starting letter, then steps of (sides to move around by, letter on that side)
+    let walk = (0..S * L, prop::collection::vec((1..S, 0..L), 2..=6));
+    let junk = prop::collection::vec(0..n_letters, 3..=7);
+    (
+        Just(letters).prop_shuffle(),
+        1..=4usize,
+        prop::collection::vec(walk, 1..=25),
+        prop::collection::vec(junk, 0..=5),
+    )
+        .prop_map(move |(letters, max_words, walks, junk)| {
+            let sides: Vec<String> = letters[..S * L]
+                .chunks(L)
+                .map(|side| side.iter().collect())
+                .collect();
+            let mut words: Vec<String> = walks
+                .iter()
+                .map(|(start, steps)| {
+                    let mut idx = *start;
+                    let mut word = String::from(letters[idx]);
+                    for (turn, letter) in steps {
+                        idx = ((idx / L + turn) % S) * L + letter;
+                        word.push(letters[idx]);
+                    }
+                    word
+                })
+                .collect();
+            words.extend(
+                junk.iter()
+                    .map(|word| word.iter().map(|&i| letters[i]).collect::<String>()),
+            );
+            (sides.join(" "), max_words, words)
+        })
+}
+
+/// The fewest words any solution can have, found by trying every chain of playable words.
+/// Deliberately knows nothing about the solvers or SmartDictionary.
+fn oracle_min_words<const S: usize, const L: usize>(
+    puzzle: &LBPuzzle<S, L>,
+    words: &[String],
+) -> Option<usize> {
+    let playable: BTreeSet<&String> = words
+        .iter()
+        .filter(|w| {
+            puzzle
+                .validate_partial_solution(&vec![w.to_string()])
+                .is_ok()
+        })
+        .collect();
+
+    let mut chains: Vec<Vec<String>> = vec![vec![]];
+    for n in 1..=puzzle.max_words() {
+        let mut next_chains = Vec::new();
+        for chain in &chains {
+            let last = chain.last().and_then(|w| w.chars().last());
+            for word in &playable {
+                if last.is_some_and(|c| !word.starts_with(c)) {
+                    continue;
+                }
+                let mut next = chain.clone();
+                next.push(word.to_string());
+                if puzzle.validate_solution(&next).is_ok() {
+                    return Some(n);
+                }
+                next_chains.push(next);
+            }
+        }
+        chains = next_chains;
+    }
+    None
+}
+
+fn check_solvers<const S: usize, const L: usize>(
+    sides: &str,
+    max_words: usize,
+    words: &[String],
+) -> Result<(), TestCaseError> {
+    let puzzle = LBPuzzle::<S, L>::from_str(max_words, sides).unwrap();
+    let dict = SmartDictionary::new_from_file(&puzzle, words.join("\n").as_bytes());
+    let oracle = oracle_min_words(&puzzle, words);
+
+    for spec in OPTIMAL_SOLVERS.iter().chain(&OTHER_SOLVERS) {
+        let solver = registry::get_solver::<S, L>(spec).unwrap();
+        let solution = solver.solve_with_dict(&puzzle, &dict);
+        match (&solution, oracle) {
+            (None, None) => {}
+            (None, Some(n)) => {
+                return Err(TestCaseError::fail(format!(
+                    "{} found nothing, but there's a {}-word solution",
+                    spec, n
+                )))
+            }
+            (Some(solution), _) => {
+                prop_assert!(
+                    puzzle.validate_solution(solution).is_ok(),
+                    "{} returned an invalid solution {:?}",
+                    spec,
+                    solution
+                );
+                prop_assert!(solution.len() <= max_words, "{}: too many words", spec);
+                prop_assert!(solution.iter().all(|w| words.contains(w)));
+                let n = oracle.unwrap_or_default();
+                if OPTIMAL_SOLVERS.contains(spec) {
+                    prop_assert_eq!(solution.len(), n, "{} isn't optimal", spec);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn prop_solvers_nyt((sides, max_words, words) in board_and_words::<4, 3>()) {
+        check_solvers::<4, 3>(&sides, max_words, &words)?;
+    }
+
+    #[test]
+    fn prop_solvers_triangle((sides, max_words, words) in board_and_words::<3, 2>()) {
+        check_solvers::<3, 2>(&sides, max_words, &words)?;
+    }
+
+    #[test]
+    fn prop_solvers_small((sides, max_words, words) in board_and_words::<2, 2>()) {
+        check_solvers::<2, 2>(&sides, max_words, &words)?;
+    }
+}
+
+proptest! {
+    // tiny boards from a tiny alphabet (including a multi-byte letter), so repeats & junk are common
+    #[test]
+    fn prop_solvers_tiny_alphabet(
+        sides in "[abcé]{2} [abcé]{2}",
+        max_words in 0..4usize,
+        words in prop::collection::vec("[abcé]{1,5}", 0..8),
+    ) {
+        // boards with repeated letters are rejected up front
+        if let Ok(puzzle) = LBPuzzle::<2, 2>::from_str(max_words, &sides) {
+            let dict = SmartDictionary::new_from_file(&puzzle, words.join("\n").as_bytes());
+            for spec in OPTIMAL_SOLVERS.iter().chain(&OTHER_SOLVERS) {
+                let solver = registry::get_solver::<2, 2>(spec).unwrap();
+                if let Some(solution) = solver.solve_with_dict(&puzzle, &dict) {
+                    prop_assert!(puzzle.validate_solution(&solution).is_ok(), "{}: {:?}", spec, solution);
+                }
+            }
+        }
+    }
+
+    // parsing & validating junk should only ever give errors, never panics
+    #[test]
+    fn prop_from_str_never_panics(sides in "\\PC*", max_words in 0..10usize) {
+        if let Ok(puzzle) = LBPuzzle::<4, 3>::from_str(max_words, &sides) {
+            prop_assert_eq!(puzzle.max_words(), max_words);
+        }
+    }
+
+    #[test]
+    fn prop_validation_never_panics(words in prop::collection::vec("\\PC{0,8}", 0..5)) {
+        let puzzle = LBPuzzle::<4, 3>::from_str(6, "erb uln imk jav").unwrap();
+        let full = puzzle.validate_solution(&words);
+        let partial = puzzle.validate_partial_solution(&words);
+        // a full solution is always a fine partial one
+        prop_assert!(full.is_err() || partial.is_ok());
+        let report = puzzle.check_solution(&words, None);
+        prop_assert_eq!(report.is_valid(), full.is_ok() && words.len() <= 6);
+    }
+
+    #[test]
+    fn prop_dictionary_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..200)) {
+        let puzzle = LBPuzzle::<4, 3>::from_str(6, "erb uln imk jav").unwrap();
+        let dict = SmartDictionary::new_from_file(&puzzle, bytes.as_slice());
+        prop_assert!(dict.len() <= dict.stats().lines_read);
+    }
+}